@@ -0,0 +1,148 @@
+use std::{env, path::PathBuf};
+
+/// Where the Vosk library and its header were found.
+struct Library {
+    lib_dirs: Vec<PathBuf>,
+    #[cfg_attr(not(feature = "bindgen"), allow(dead_code))]
+    include_dirs: Vec<PathBuf>,
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=VOSK_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=VOSK_INCLUDE_DIR");
+    println!("cargo:rustc-check-cfg=cfg(vosk_bindgen)");
+
+    let library = find_library();
+
+    // With dynamic loading, the library is not needed until runtime, and the mock does not need it
+    if env::var_os("CARGO_FEATURE_DYNAMIC_LOAD").is_none()
+        && env::var_os("CARGO_FEATURE_MOCK").is_none()
+    {
+        link(&library);
+    }
+
+    // The mock only implements the entry points of the bundled bindings
+    #[cfg(feature = "bindgen")]
+    if env::var_os("CARGO_FEATURE_MOCK").is_none() {
+        generate_bindings(&library);
+    }
+}
+
+/// Looks for the library in `VOSK_LIB_DIR`, then with `pkg-config`.
+///
+/// If neither of them finds it, the linker looks for it in its default
+/// search paths and the ones passed with `RUSTFLAGS`.
+fn find_library() -> Library {
+    let include_dir = env::var_os("VOSK_INCLUDE_DIR").map(PathBuf::from);
+
+    if let Some(lib_dir) = env::var_os("VOSK_LIB_DIR") {
+        return Library {
+            lib_dirs: vec![PathBuf::from(lib_dir)],
+            include_dirs: include_dir.into_iter().collect(),
+        };
+    }
+
+    // The `#[link]` attributes already link the library, so only the paths are needed
+    let probed = pkg_config::Config::new()
+        .cargo_metadata(false)
+        .env_metadata(true)
+        .probe("vosk");
+
+    let (lib_dirs, include_dirs) = probed
+        .map(|library| (library.link_paths, library.include_paths))
+        .unwrap_or_default();
+
+    Library {
+        lib_dirs,
+        include_dirs: include_dir.into_iter().chain(include_dirs).collect(),
+    }
+}
+
+/// Adds the directories of the library to the linker's search paths and, with
+/// the `rpath` feature, to the paths searched by the executable at runtime.
+fn link(library: &Library) {
+    for dir in &library.lib_dirs {
+        println!("cargo:rustc-link-search=native={}", dir.display());
+    }
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if env::var_os("CARGO_FEATURE_RPATH").is_none()
+        || target_os == "windows"
+        || library.lib_dirs.is_empty()
+    {
+        return;
+    }
+
+    // Link arguments only apply to the targets of this package, so the directories are
+    // also passed to the build scripts of dependents as `DEP_VOSK_RPATH`
+    for dir in &library.lib_dirs {
+        println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
+    }
+    let rpath = env::join_paths(&library.lib_dirs).expect("library directory contains a separator");
+    println!("cargo:rpath={}", rpath.to_string_lossy());
+}
+
+/// Generates the bindings from the `vosk_api.h` in the include directories, which are
+/// used instead of the bundled ones. If the header is not found, the bundled ones are kept.
+#[cfg(feature = "bindgen")]
+fn generate_bindings(library: &Library) {
+    let Some(header) = library
+        .include_dirs
+        .iter()
+        .map(|dir| dir.join("vosk_api.h"))
+        .find(|header| header.is_file())
+    else {
+        println!(
+            "cargo:warning=vosk_api.h was not found, set VOSK_INCLUDE_DIR to the directory \
+             that contains it to regenerate the bindings. Using the bundled bindings instead"
+        );
+        return;
+    };
+    println!("cargo:rerun-if-changed={}", header.display());
+
+    let bindings = bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        .allowlist_function("vosk_.*")
+        .allowlist_type("Vosk.*")
+        .allowlist_var("Vosk.*")
+        .merge_extern_blocks(true)
+        .layout_tests(false)
+        .generate()
+        .unwrap_or_else(|err| {
+            panic!(
+                "could not generate bindings from {}: {err}",
+                header.display()
+            )
+        });
+
+    let out_path =
+        PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo")).join("bindings.rs");
+    std::fs::write(&out_path, wrap_functions(&bindings.to_string()))
+        .unwrap_or_else(|err| panic!("could not write {}: {err}", out_path.display()));
+
+    println!("cargo:rustc-cfg=vosk_bindgen");
+}
+
+/// Wraps the `extern` blocks generated by bindgen in `vosk_functions!`, like the bundled bindings,
+/// so that they are linked or loaded at runtime depending on the `dynamic-load` feature.
+#[cfg(feature = "bindgen")]
+fn wrap_functions(bindings: &str) -> String {
+    let mut wrapped = String::with_capacity(bindings.len());
+    let mut in_block = false;
+
+    for line in bindings.lines() {
+        if !in_block && line.trim_start_matches("unsafe ") == "extern \"C\" {" {
+            wrapped.push_str("vosk_functions! {\n    generated,\n    extern \"C\" {\n");
+            in_block = true;
+        } else if in_block && line == "}" {
+            wrapped.push_str("    }\n}\n");
+            in_block = false;
+        } else {
+            wrapped.push_str(line);
+            wrapped.push('\n');
+        }
+    }
+
+    wrapped
+}
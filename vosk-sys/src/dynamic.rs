@@ -0,0 +1,210 @@
+use libloading::Library;
+use std::{
+    env,
+    ffi::OsStr,
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+/// File name of the Vosk library on the current platform.
+#[cfg(target_os = "windows")]
+pub const LIBRARY_NAME: &str = "libvosk.dll";
+/// File name of the Vosk library on the current platform.
+#[cfg(target_os = "macos")]
+pub const LIBRARY_NAME: &str = "libvosk.dylib";
+/// File name of the Vosk library on the current platform.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub const LIBRARY_NAME: &str = "libvosk.so";
+
+/// The loaded library and the entry points resolved from it.
+#[cfg_attr(feature = "mock", allow(dead_code))]
+pub(crate) struct Loaded {
+    // Kept so that the library is never unloaded while the entry points are in use
+    _library: Library,
+    #[cfg(not(vosk_bindgen))]
+    pub(crate) v0_3_42: crate::v0_3_42::Functions,
+    #[cfg(all(not(vosk_bindgen), feature = "vosk-0-3-45"))]
+    pub(crate) v0_3_45: crate::v0_3_45::Functions,
+    #[cfg(all(not(vosk_bindgen), feature = "vosk-0-3-50"))]
+    pub(crate) v0_3_50: crate::v0_3_50::Functions,
+    #[cfg(vosk_bindgen)]
+    pub(crate) generated: crate::generated::Functions,
+}
+
+static LOADED: OnceLock<Loaded> = OnceLock::new();
+
+/// Serializes the attempts to load the library, so that it is only opened once.
+static LOADING: Mutex<()> = Mutex::new(());
+
+/// Error returned when the Vosk library could not be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// A library has already been loaded.
+    AlreadyLoaded,
+
+    /// The library at the given path could not be opened.
+    Open {
+        /// Path that was passed to the system's loader.
+        path: PathBuf,
+
+        /// Error returned by the system's loader.
+        source: libloading::Error,
+    },
+
+    /// The library at the given path does not have one of the entry points,
+    /// probably because it is older than the version enabled with the features.
+    MissingSymbol {
+        /// Path the library was loaded from.
+        path: PathBuf,
+
+        /// Name of the missing entry point.
+        symbol: &'static str,
+
+        /// Error returned by the system's loader.
+        source: libloading::Error,
+    },
+
+    /// None of the paths that were tried could be opened.
+    NotFound {
+        /// Errors for each of the paths, in the order they were tried.
+        errors: Vec<LoadError>,
+    },
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyLoaded => write!(f, "the Vosk library has already been loaded"),
+            Self::Open { path, source } => {
+                write!(f, "could not open {}: {source}", path.display())
+            }
+            Self::MissingSymbol { path, symbol, .. } => {
+                write!(f, "{} does not export {symbol}", path.display())
+            }
+            Self::NotFound { errors } => {
+                write!(f, "none of the paths could be opened")?;
+                for error in errors {
+                    write!(f, "\n  {error}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Open { source, .. } | Self::MissingSymbol { source, .. } => Some(source),
+            Self::AlreadyLoaded | Self::NotFound { .. } => None,
+        }
+    }
+}
+
+/// Loads the Vosk library from `path`, which is passed to the system's loader, so
+/// it can be either a path to the library or a file name to look for in the usual places.
+///
+/// Fails with [`LoadError::AlreadyLoaded`] if a library has already been loaded,
+/// or always with the `mock` feature.
+pub fn load(path: impl AsRef<OsStr>) -> Result<(), LoadError> {
+    let _guard = LOADING.lock().unwrap_or_else(|err| err.into_inner());
+    if is_loaded() {
+        return Err(LoadError::AlreadyLoaded);
+    }
+
+    let loaded = open(Path::new(path.as_ref()))?;
+    // Cannot fail, as the library is only ever set while holding the lock
+    let _ = LOADED.set(loaded);
+
+    Ok(())
+}
+
+/// Loads the Vosk library from the first of `paths` that can be opened.
+///
+/// A library that can be opened but lacks some of the entry points stops the search,
+/// as it is most likely the one that was meant to be used, but with the wrong version.
+pub fn load_from<I>(paths: I) -> Result<(), LoadError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<OsStr>,
+{
+    let mut errors = Vec::new();
+    for path in paths {
+        match load(path) {
+            Err(err @ LoadError::Open { .. }) => errors.push(err),
+            result => return result,
+        }
+    }
+
+    Err(LoadError::NotFound { errors })
+}
+
+/// Returns the paths the library is loaded from when an entry point is
+/// called before loading it explicitly, in the order they are tried:
+///
+/// 1. [`LIBRARY_NAME`] inside of the directory in the `VOSK_LIB_DIR` environment variable.
+/// 2. [`LIBRARY_NAME`] next to the current executable.
+/// 3. [`LIBRARY_NAME`] alone, which makes the system's loader look for it.
+#[must_use]
+pub fn default_search_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(dir) = env::var_os("VOSK_LIB_DIR") {
+        paths.push(Path::new(&dir).join(LIBRARY_NAME));
+    }
+    if let Some(dir) = env::current_exe().ok().as_deref().and_then(Path::parent) {
+        paths.push(dir.join(LIBRARY_NAME));
+    }
+    paths.push(PathBuf::from(LIBRARY_NAME));
+
+    paths
+}
+
+/// Returns whether the Vosk library has been loaded.
+///
+/// Always true with the `mock` feature, as the entry points do not need the library.
+#[must_use]
+pub fn is_loaded() -> bool {
+    cfg!(feature = "mock") || LOADED.get().is_some()
+}
+
+fn open(path: &Path) -> Result<Loaded, LoadError> {
+    let library = unsafe { Library::new(path) }.map_err(|source| LoadError::Open {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    Ok(Loaded {
+        #[cfg(not(vosk_bindgen))]
+        v0_3_42: crate::v0_3_42::Functions::resolve(&library, path)?,
+        #[cfg(all(not(vosk_bindgen), feature = "vosk-0-3-45"))]
+        v0_3_45: crate::v0_3_45::Functions::resolve(&library, path)?,
+        #[cfg(all(not(vosk_bindgen), feature = "vosk-0-3-50"))]
+        v0_3_50: crate::v0_3_50::Functions::resolve(&library, path)?,
+        #[cfg(vosk_bindgen)]
+        generated: crate::generated::Functions::resolve(&library, path)?,
+        _library: library,
+    })
+}
+
+/// Returns the loaded entry points, loading the library from
+/// the [`default_search_paths`] if it has not been loaded yet.
+///
+/// # Panics
+///
+/// Panics if the library could not be loaded, as the entry points cannot report errors.
+/// The panic cannot unwind out of the entry points, so it aborts the process.
+#[cfg_attr(feature = "mock", allow(dead_code))]
+pub(crate) fn functions() -> &'static Loaded {
+    if let Some(loaded) = LOADED.get() {
+        return loaded;
+    }
+
+    match load_from(default_search_paths()) {
+        Ok(()) | Err(LoadError::AlreadyLoaded) => {}
+        Err(err) => panic!("{err}"),
+    }
+
+    LOADED.get().expect("the library was just loaded")
+}
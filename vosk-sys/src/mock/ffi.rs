@@ -0,0 +1,454 @@
+//! Implementations of the entry points for the mock, called by the ones declared in the bindings.
+
+use super::{Mock, MockRecognizer};
+use crate::{VoskBatchModel, VoskBatchRecognizer, VoskModel, VoskRecognizer, VoskSpkModel};
+use std::{
+    collections::VecDeque,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int, c_short},
+    ptr,
+};
+
+struct ModelHandle {
+    mock: Mock,
+}
+
+struct RecognizerHandle {
+    recognizer: MockRecognizer,
+
+    /// Last result returned, which has to stay valid until the next call.
+    output: CString,
+}
+
+struct BatchRecognizerHandle {
+    recognizer: MockRecognizer,
+    results: VecDeque<CString>,
+}
+
+/// Moves `value` to the heap and returns it as a pointer to the opaque type `T`.
+fn into_ptr<H, T>(value: H) -> *mut T {
+    Box::into_raw(Box::new(value)).cast()
+}
+
+/// Returns the handle behind `ptr`, which was created with [`into_ptr`].
+///
+/// # Safety
+///
+/// `ptr` must have been created with [`into_ptr`] from an `H`, and not freed yet.
+unsafe fn handle<'a, H, T>(ptr: *mut T) -> &'a mut H {
+    &mut *ptr.cast::<H>()
+}
+
+/// Frees the handle behind `ptr`, created with [`into_ptr`].
+///
+/// # Safety
+///
+/// Same as [`handle`].
+unsafe fn free<H, T>(ptr: *mut T) -> H {
+    *Box::from_raw(ptr.cast::<H>())
+}
+
+fn to_cstring(text: String) -> CString {
+    CString::new(text).unwrap_or_else(|err| {
+        let mut bytes = err.into_vec();
+        bytes.retain(|&byte| byte != 0);
+        CString::new(bytes).expect("NUL bytes were removed")
+    })
+}
+
+unsafe fn to_string(text: *const c_char) -> String {
+    CStr::from_ptr(text).to_string_lossy().into_owned()
+}
+
+fn new_model<T>() -> *mut T {
+    let mock = Mock::current();
+    if mock.model_load_fails() {
+        ptr::null_mut()
+    } else {
+        into_ptr(ModelHandle { mock })
+    }
+}
+
+pub(crate) unsafe fn vosk_model_new(_model_path: *const c_char) -> *mut VoskModel {
+    new_model()
+}
+
+pub(crate) unsafe fn vosk_model_free(model: *mut VoskModel) {
+    free::<ModelHandle, _>(model);
+}
+
+pub(crate) unsafe fn vosk_model_find_word(model: *mut VoskModel, word: *const c_char) -> c_int {
+    handle::<ModelHandle, _>(model)
+        .mock
+        .find_word(&to_string(word))
+}
+
+pub(crate) unsafe fn vosk_spk_model_new(_model_path: *const c_char) -> *mut VoskSpkModel {
+    new_model()
+}
+
+pub(crate) unsafe fn vosk_spk_model_free(model: *mut VoskSpkModel) {
+    free::<ModelHandle, _>(model);
+}
+
+pub(crate) unsafe fn vosk_recognizer_new(
+    model: *mut VoskModel,
+    sample_rate: f32,
+) -> *mut VoskRecognizer {
+    match handle::<ModelHandle, _>(model)
+        .mock
+        .next_recognizer(sample_rate)
+    {
+        Some(recognizer) => into_ptr(RecognizerHandle {
+            recognizer,
+            output: CString::default(),
+        }),
+        None => ptr::null_mut(),
+    }
+}
+
+pub(crate) unsafe fn vosk_recognizer_new_spk(
+    model: *mut VoskModel,
+    sample_rate: f32,
+    spk_model: *mut VoskSpkModel,
+) -> *mut VoskRecognizer {
+    let recognizer = vosk_recognizer_new(model, sample_rate);
+    if !recognizer.is_null() {
+        vosk_recognizer_set_spk_model(recognizer, spk_model);
+    }
+
+    recognizer
+}
+
+pub(crate) unsafe fn vosk_recognizer_new_grm(
+    model: *mut VoskModel,
+    sample_rate: f32,
+    grammar: *const c_char,
+) -> *mut VoskRecognizer {
+    let recognizer = vosk_recognizer_new(model, sample_rate);
+    if !recognizer.is_null() {
+        set_grammar(recognizer, grammar);
+    }
+
+    recognizer
+}
+
+unsafe fn set_grammar(recognizer: *mut VoskRecognizer, grammar: *const c_char) {
+    handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .grammar = Some(to_string(grammar));
+}
+
+pub(crate) unsafe fn vosk_recognizer_set_spk_model(
+    recognizer: *mut VoskRecognizer,
+    _spk_model: *mut VoskSpkModel,
+) {
+    handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .has_speaker_model = true;
+}
+
+pub(crate) unsafe fn vosk_recognizer_set_max_alternatives(
+    recognizer: *mut VoskRecognizer,
+    max_alternatives: c_int,
+) {
+    handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .max_alternatives = max_alternatives;
+}
+
+pub(crate) unsafe fn vosk_recognizer_set_words(recognizer: *mut VoskRecognizer, words: c_int) {
+    handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .words = words != 0;
+}
+
+pub(crate) unsafe fn vosk_recognizer_set_partial_words(
+    recognizer: *mut VoskRecognizer,
+    partial_words: c_int,
+) {
+    handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .partial_words = partial_words != 0;
+}
+
+pub(crate) unsafe fn vosk_recognizer_set_nlsml(recognizer: *mut VoskRecognizer, nlsml: c_int) {
+    handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .nlsml = nlsml != 0;
+}
+
+pub(crate) unsafe fn vosk_recognizer_accept_waveform(
+    recognizer: *mut VoskRecognizer,
+    data: *const c_char,
+    length: c_int,
+) -> c_int {
+    accept(recognizer, &pcm16_bytes(data, length))
+}
+
+pub(crate) unsafe fn vosk_recognizer_accept_waveform_s(
+    recognizer: *mut VoskRecognizer,
+    data: *const c_short,
+    length: c_int,
+) -> c_int {
+    let samples: Vec<f32> = slice(data, length)
+        .iter()
+        .map(|&sample| f32::from(sample))
+        .collect();
+    accept(recognizer, &samples)
+}
+
+pub(crate) unsafe fn vosk_recognizer_accept_waveform_f(
+    recognizer: *mut VoskRecognizer,
+    data: *const f32,
+    length: c_int,
+) -> c_int {
+    accept(recognizer, slice(data, length))
+}
+
+unsafe fn accept(recognizer: *mut VoskRecognizer, samples: &[f32]) -> c_int {
+    handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .accept(samples)
+        .0
+}
+
+/// Returns the `length` values at `data`, which may be dangling if there are none.
+unsafe fn slice<'a, T>(data: *const T, length: c_int) -> &'a [T] {
+    if length > 0 {
+        std::slice::from_raw_parts(data, length as usize)
+    } else {
+        &[]
+    }
+}
+
+/// Reads `length` bytes of 16-bit PCM audio in native endian, as Vosk does.
+unsafe fn pcm16_bytes(data: *const c_char, length: c_int) -> Vec<f32> {
+    slice(data.cast::<u8>(), length)
+        .chunks_exact(2)
+        .map(|bytes| f32::from(i16::from_ne_bytes([bytes[0], bytes[1]])))
+        .collect()
+}
+
+/// Stores `result` in the handle, so that it stays valid until the next call, and returns it.
+unsafe fn output(recognizer: *mut VoskRecognizer, result: String) -> *const c_char {
+    let handle = handle::<RecognizerHandle, _>(recognizer);
+    handle.output = to_cstring(result);
+    handle.output.as_ptr()
+}
+
+pub(crate) unsafe fn vosk_recognizer_result(recognizer: *mut VoskRecognizer) -> *const c_char {
+    let result = handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .result();
+    output(recognizer, result)
+}
+
+pub(crate) unsafe fn vosk_recognizer_partial_result(
+    recognizer: *mut VoskRecognizer,
+) -> *const c_char {
+    let result = handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .partial_result();
+    output(recognizer, result)
+}
+
+pub(crate) unsafe fn vosk_recognizer_final_result(
+    recognizer: *mut VoskRecognizer,
+) -> *const c_char {
+    let result = handle::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .take_final_result()
+        .unwrap_or_else(|| super::EMPTY_RESULT.to_owned());
+    output(recognizer, result)
+}
+
+pub(crate) unsafe fn vosk_recognizer_reset(recognizer: *mut VoskRecognizer) {
+    handle::<RecognizerHandle, _>(recognizer).recognizer.reset();
+}
+
+pub(crate) unsafe fn vosk_recognizer_free(recognizer: *mut VoskRecognizer) {
+    free::<RecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .freed = true;
+}
+
+pub(crate) unsafe fn vosk_set_log_level(_log_level: c_int) {}
+
+pub(crate) unsafe fn vosk_gpu_init() {}
+
+pub(crate) unsafe fn vosk_gpu_thread_init() {}
+
+pub(crate) unsafe fn vosk_batch_model_new() -> *mut VoskBatchModel {
+    new_model()
+}
+
+pub(crate) unsafe fn vosk_batch_model_free(model: *mut VoskBatchModel) {
+    free::<ModelHandle, _>(model);
+}
+
+pub(crate) unsafe fn vosk_batch_model_wait(_model: *mut VoskBatchModel) {}
+
+pub(crate) unsafe fn vosk_batch_recognizer_new(
+    model: *mut VoskBatchModel,
+    sample_rate: f32,
+) -> *mut VoskBatchRecognizer {
+    match handle::<ModelHandle, _>(model)
+        .mock
+        .next_recognizer(sample_rate)
+    {
+        Some(recognizer) => into_ptr(BatchRecognizerHandle {
+            recognizer,
+            results: VecDeque::new(),
+        }),
+        None => ptr::null_mut(),
+    }
+}
+
+pub(crate) unsafe fn vosk_batch_recognizer_free(recognizer: *mut VoskBatchRecognizer) {
+    free::<BatchRecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .freed = true;
+}
+
+pub(crate) unsafe fn vosk_batch_recognizer_accept_waveform(
+    recognizer: *mut VoskBatchRecognizer,
+    data: *const c_char,
+    length: c_int,
+) {
+    let handle = handle::<BatchRecognizerHandle, _>(recognizer);
+    // Results are queued instead of being retrieved with vosk_recognizer_result
+    if let (_, Some(result)) = handle.recognizer.accept(&pcm16_bytes(data, length)) {
+        handle.recognizer.lock().result = None;
+        handle.results.push_back(to_cstring(result));
+    }
+}
+
+pub(crate) unsafe fn vosk_batch_recognizer_set_nlsml(
+    recognizer: *mut VoskBatchRecognizer,
+    nlsml: c_int,
+) {
+    handle::<BatchRecognizerHandle, _>(recognizer)
+        .recognizer
+        .lock()
+        .nlsml = nlsml != 0;
+}
+
+pub(crate) unsafe fn vosk_batch_recognizer_finish_stream(recognizer: *mut VoskBatchRecognizer) {
+    let handle = handle::<BatchRecognizerHandle, _>(recognizer);
+    if let Some(result) = handle.recognizer.take_final_result() {
+        handle.results.push_back(to_cstring(result));
+    }
+}
+
+pub(crate) unsafe fn vosk_batch_recognizer_front_result(
+    recognizer: *mut VoskBatchRecognizer,
+) -> *const c_char {
+    // Vosk returns an empty string when there are no results
+    handle::<BatchRecognizerHandle, _>(recognizer)
+        .results
+        .front()
+        .map_or(c"".as_ptr(), |result| result.as_ptr())
+}
+
+pub(crate) unsafe fn vosk_batch_recognizer_pop(recognizer: *mut VoskBatchRecognizer) {
+    handle::<BatchRecognizerHandle, _>(recognizer)
+        .results
+        .pop_front();
+}
+
+pub(crate) unsafe fn vosk_batch_recognizer_get_pending_chunks(
+    _recognizer: *mut VoskBatchRecognizer,
+) -> c_int {
+    // Chunks are processed as soon as they are accepted
+    0
+}
+
+#[cfg(feature = "vosk-0-3-45")]
+pub(crate) unsafe fn vosk_recognizer_set_grm(
+    recognizer: *mut VoskRecognizer,
+    grammar: *const c_char,
+) {
+    set_grammar(recognizer, grammar);
+}
+
+#[cfg(feature = "vosk-0-3-50")]
+pub(crate) use v0_3_50::*;
+
+#[cfg(feature = "vosk-0-3-50")]
+mod v0_3_50 {
+    use super::{free, handle, new_model, to_string, Mock, ModelHandle, RecognizerHandle};
+    use crate::{VoskEndpointerMode, VoskRecognizer, VoskTextProcessor};
+    use std::{
+        ffi::c_void,
+        os::raw::c_char,
+        ptr::{self, NonNull},
+    };
+
+    extern "C" {
+        // The text returned by vosk_text_processor_itn is released with free on Unix
+        fn malloc(size: usize) -> *mut c_void;
+    }
+
+    pub(crate) unsafe fn vosk_recognizer_set_endpointer_mode(
+        recognizer: *mut VoskRecognizer,
+        mode: VoskEndpointerMode,
+    ) {
+        handle::<RecognizerHandle, _>(recognizer)
+            .recognizer
+            .lock()
+            .endpointer_mode = Some(mode);
+    }
+
+    pub(crate) unsafe fn vosk_recognizer_set_endpointer_delays(
+        recognizer: *mut VoskRecognizer,
+        t_start_max: f32,
+        t_end: f32,
+        t_max: f32,
+    ) {
+        handle::<RecognizerHandle, _>(recognizer)
+            .recognizer
+            .lock()
+            .endpointer_delays = Some((t_start_max, t_end, t_max));
+    }
+
+    pub(crate) unsafe fn vosk_text_processor_new(
+        tagger: *const c_char,
+        verbalizer: *const c_char,
+    ) -> *mut VoskTextProcessor {
+        Mock::current().add_text_processor(to_string(tagger), to_string(verbalizer));
+        new_model()
+    }
+
+    pub(crate) unsafe fn vosk_text_processor_free(processor: *mut VoskTextProcessor) {
+        free::<ModelHandle, _>(processor);
+    }
+
+    pub(crate) unsafe fn vosk_text_processor_itn(
+        processor: *mut VoskTextProcessor,
+        input: *const c_char,
+    ) -> *mut c_char {
+        let Some(text) = handle::<ModelHandle, _>(processor)
+            .mock
+            .itn_text(&to_string(input))
+        else {
+            return ptr::null_mut();
+        };
+
+        let Some(output) = NonNull::new(malloc(text.len() + 1).cast::<c_char>()) else {
+            return ptr::null_mut();
+        };
+        ptr::copy_nonoverlapping(text.as_ptr().cast(), output.as_ptr(), text.len());
+        *output.as_ptr().add(text.len()) = 0;
+
+        output.as_ptr()
+    }
+}
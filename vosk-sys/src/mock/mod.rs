@@ -0,0 +1,481 @@
+//! A mock of the Vosk library written in Rust, enabled with the `mock` feature.
+//!
+//! With this feature, the entry points of this crate do not decode any audio. Instead, they
+//! behave as scripted with [`Mock`] and [`MockRecognizer`], so that code using them can be
+//! tested without the library or a model. The library is neither linked nor loaded at runtime.
+//!
+//! A [`Mock`] is installed for the current thread with [`Mock::install`]. Models created while
+//! it is installed keep using it, as do the recognizers created from them, even if they are
+//! moved to other threads. Models created when no mock is installed use [`Mock::new`].
+//!
+//! # Examples
+//!
+//! ```
+//! use vosk_sys::mock::{text_json, Mock, MockRecognizer};
+//! use vosk_sys::*;
+//!
+//! let recognizer = MockRecognizer::new()
+//!     .partial(r#"{"partial": "hello"}"#)
+//!     .finalized(text_json("hello world"));
+//! let _guard = Mock::new().recognizer(recognizer.clone()).install();
+//!
+//! unsafe {
+//!     let model = vosk_model_new(c"model".as_ptr());
+//!     let rec = vosk_recognizer_new(model, 16000.0);
+//!
+//!     let samples = [0i16; 160];
+//!     assert_eq!(vosk_recognizer_accept_waveform_s(rec, samples.as_ptr(), 160), 0);
+//!     assert_eq!(vosk_recognizer_accept_waveform_s(rec, samples.as_ptr(), 160), 1);
+//!
+//!     vosk_recognizer_free(rec);
+//!     vosk_model_free(model);
+//! }
+//!
+//! assert_eq!(recognizer.accepted_samples(), 320);
+//! ```
+
+pub(crate) mod ffi;
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    marker::PhantomData,
+    os::raw::c_int,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Result returned when no other one has been scripted.
+const EMPTY_RESULT: &str = r#"{"text" : ""}"#;
+
+/// Partial result returned when no other one has been scripted.
+const EMPTY_PARTIAL_RESULT: &str = r#"{"partial" : ""}"#;
+
+thread_local! {
+    static INSTALLED: RefCell<Option<Mock>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    model_load_fails: bool,
+    recognizer_creation_fails: bool,
+    vocabulary: Option<Vec<String>>,
+    recognizers: VecDeque<MockRecognizer>,
+    #[cfg_attr(not(feature = "vosk-0-3-50"), allow(dead_code))]
+    itn: Vec<(String, String)>,
+    #[cfg_attr(not(feature = "vosk-0-3-50"), allow(dead_code))]
+    itn_fails: bool,
+    #[cfg_attr(not(feature = "vosk-0-3-50"), allow(dead_code))]
+    text_processors: Vec<(String, String)>,
+}
+
+/// Scripted behaviour of the mocked library.
+///
+/// Clones share the same script, so recognizers can still be queued after installing it.
+#[derive(Debug, Clone, Default)]
+pub struct Mock(Arc<Mutex<MockState>>);
+
+impl Mock {
+    /// Returns a mock where models load successfully, every word is in
+    /// the vocabulary and recognizers follow the default [`MockRecognizer`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the constructors of models, speaker models, batch models and
+    /// text processors fail, returning a null pointer.
+    #[must_use]
+    pub fn fail_model_load(self) -> Self {
+        self.lock().model_load_fails = true;
+        self
+    }
+
+    /// Makes the constructors of recognizers and batch recognizers fail, returning a null pointer.
+    #[must_use]
+    pub fn fail_recognizer_creation(self) -> Self {
+        self.lock().recognizer_creation_fails = true;
+        self
+    }
+
+    /// Sets the words known by models. `vosk_model_find_word` returns the position of
+    /// each word starting at 1, as symbol 0 is `<eps>`, and -1 for any other word.
+    ///
+    /// By default, every word is known and has the symbol 1.
+    #[must_use]
+    pub fn vocabulary<S: Into<String>>(self, words: impl IntoIterator<Item = S>) -> Self {
+        self.lock().vocabulary = Some(words.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Queues the script for the next recognizer created from a model using this mock.
+    ///
+    /// Recognizers created once the queue is empty follow the default [`MockRecognizer`].
+    #[must_use]
+    pub fn recognizer(self, recognizer: MockRecognizer) -> Self {
+        self.lock().recognizers.push_back(recognizer);
+        self
+    }
+
+    /// Makes `vosk_text_processor_itn` replace `spoken` with `written`.
+    /// Replacements are applied in the order they were added.
+    #[cfg(feature = "vosk-0-3-50")]
+    #[must_use]
+    pub fn itn(self, spoken: impl Into<String>, written: impl Into<String>) -> Self {
+        self.lock().itn.push((spoken.into(), written.into()));
+        self
+    }
+
+    /// Makes `vosk_text_processor_itn` fail, returning a null pointer.
+    #[cfg(feature = "vosk-0-3-50")]
+    #[must_use]
+    pub fn fail_itn(self) -> Self {
+        self.lock().itn_fails = true;
+        self
+    }
+
+    /// Returns the paths of the tagger and verbalizer passed to
+    /// each call to `vosk_text_processor_new`, in order.
+    #[cfg(feature = "vosk-0-3-50")]
+    #[must_use]
+    pub fn text_processors(&self) -> Vec<(String, String)> {
+        self.lock().text_processors.clone()
+    }
+
+    /// Installs the mock for the current thread until the returned guard is dropped,
+    /// which restores the mock that was installed before.
+    #[must_use = "the mock is uninstalled when the guard is dropped"]
+    pub fn install(&self) -> MockGuard {
+        let previous = INSTALLED.with(|installed| installed.replace(Some(self.clone())));
+
+        MockGuard {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the mock installed for the current thread, or a new one.
+    pub(crate) fn current() -> Self {
+        INSTALLED
+            .with(|installed| installed.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub(crate) fn model_load_fails(&self) -> bool {
+        self.lock().model_load_fails
+    }
+
+    /// Returns the script for a new recognizer, or [`None`] if its creation should fail.
+    pub(crate) fn next_recognizer(&self, sample_rate: f32) -> Option<MockRecognizer> {
+        let mut state = self.lock();
+        if state.recognizer_creation_fails {
+            return None;
+        }
+
+        let recognizer = state.recognizers.pop_front().unwrap_or_default();
+        recognizer.lock().sample_rate = sample_rate;
+        Some(recognizer)
+    }
+
+    pub(crate) fn find_word(&self, word: &str) -> c_int {
+        match &self.lock().vocabulary {
+            Some(vocabulary) => vocabulary
+                .iter()
+                .position(|known| known == word)
+                .map_or(-1, |index| index as c_int + 1),
+            None => 1,
+        }
+    }
+
+    #[cfg(feature = "vosk-0-3-50")]
+    pub(crate) fn add_text_processor(&self, tagger: String, verbalizer: String) {
+        self.lock().text_processors.push((tagger, verbalizer));
+    }
+
+    /// Returns the normalized `text`, or [`None`] if normalization should fail.
+    #[cfg(feature = "vosk-0-3-50")]
+    pub(crate) fn itn_text(&self, text: &str) -> Option<String> {
+        let state = self.lock();
+        if state.itn_fails {
+            return None;
+        }
+
+        Some(
+            state
+                .itn
+                .iter()
+                .fold(text.to_owned(), |text, (spoken, written)| {
+                    text.replace(spoken.as_str(), written)
+                }),
+        )
+    }
+}
+
+/// Uninstalls a [`Mock`] when dropped. Returned by [`Mock::install`].
+#[derive(Debug)]
+pub struct MockGuard {
+    previous: Option<Mock>,
+    // The mock is installed for the thread that created the guard
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        INSTALLED.with(|installed| *installed.borrow_mut() = self.previous.take());
+    }
+}
+
+/// What happens when a chunk of audio is accepted.
+#[derive(Debug)]
+enum Step {
+    Partial(String),
+    Finalized(String),
+    Failed,
+}
+
+#[derive(Debug, Default)]
+struct RecognizerState {
+    steps: VecDeque<Step>,
+    final_result: Option<String>,
+    partial: Option<String>,
+    result: Option<String>,
+
+    sample_rate: f32,
+    samples: Vec<f32>,
+    resets: usize,
+    grammar: Option<String>,
+    max_alternatives: c_int,
+    words: bool,
+    partial_words: bool,
+    nlsml: bool,
+    has_speaker_model: bool,
+    #[cfg(feature = "vosk-0-3-50")]
+    endpointer_mode: Option<crate::VoskEndpointerMode>,
+    #[cfg(feature = "vosk-0-3-50")]
+    endpointer_delays: Option<(f32, f32, f32)>,
+    freed: bool,
+}
+
+/// Script of a mocked recognizer, and a record of how it was used.
+///
+/// Each chunk of audio accepted by the recognizer consumes the next step of the script.
+/// Once all of them have been consumed, chunks are accepted without changing the results.
+/// Clones share the same script and record, so a clone can be kept to check how the
+/// recognizer was configured after queuing it with [`Mock::recognizer`].
+///
+/// Results are returned as scripted, so they can also be invalid to test error handling.
+#[derive(Debug, Clone, Default)]
+pub struct MockRecognizer(Arc<Mutex<RecognizerState>>);
+
+impl MockRecognizer {
+    /// Returns a script without any steps, where all the results are empty.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step where decoding continues (`vosk_recognizer_accept_waveform`
+    /// returns 0) and the partial result becomes `json`.
+    #[must_use]
+    pub fn partial(self, json: impl Into<String>) -> Self {
+        self.lock().steps.push_back(Step::Partial(json.into()));
+        self
+    }
+
+    /// Adds a step where the utterance ends (`vosk_recognizer_accept_waveform` returns 1)
+    /// and `json` is returned by the next call to `vosk_recognizer_result`.
+    #[must_use]
+    pub fn finalized(self, json: impl Into<String>) -> Self {
+        self.lock().steps.push_back(Step::Finalized(json.into()));
+        self
+    }
+
+    /// Adds a step where decoding fails (`vosk_recognizer_accept_waveform` returns -1).
+    #[must_use]
+    pub fn failed(self) -> Self {
+        self.lock().steps.push_back(Step::Failed);
+        self
+    }
+
+    /// Sets the result returned by the next call to `vosk_recognizer_final_result`.
+    ///
+    /// Otherwise, it returns the result of the last finalized utterance if it has
+    /// not been retrieved, or an empty result.
+    #[must_use]
+    pub fn final_result(self, json: impl Into<String>) -> Self {
+        self.lock().final_result = Some(json.into());
+        self
+    }
+
+    /// Returns the sample rate the recognizer was created with.
+    #[must_use]
+    pub fn sample_rate(&self) -> f32 {
+        self.lock().sample_rate
+    }
+
+    /// Returns the amount of samples accepted by the recognizer.
+    #[must_use]
+    pub fn accepted_samples(&self) -> usize {
+        self.lock().samples.len()
+    }
+
+    /// Returns the samples accepted by the recognizer, as 16-bit PCM values.
+    #[must_use]
+    pub fn samples(&self) -> Vec<f32> {
+        self.lock().samples.clone()
+    }
+
+    /// Returns the amount of steps that have not been consumed yet.
+    #[must_use]
+    pub fn remaining_steps(&self) -> usize {
+        self.lock().steps.len()
+    }
+
+    /// Returns how many times the recognizer has been reset.
+    #[must_use]
+    pub fn resets(&self) -> usize {
+        self.lock().resets
+    }
+
+    /// Returns the last grammar passed to the recognizer, if any.
+    #[must_use]
+    pub fn grammar(&self) -> Option<String> {
+        self.lock().grammar.clone()
+    }
+
+    /// Returns the last value passed to `vosk_recognizer_set_max_alternatives`, 0 by default.
+    #[must_use]
+    pub fn max_alternatives(&self) -> c_int {
+        self.lock().max_alternatives
+    }
+
+    /// Returns whether words are enabled with `vosk_recognizer_set_words`.
+    #[must_use]
+    pub fn words(&self) -> bool {
+        self.lock().words
+    }
+
+    /// Returns whether partial words are enabled with `vosk_recognizer_set_partial_words`.
+    #[must_use]
+    pub fn partial_words(&self) -> bool {
+        self.lock().partial_words
+    }
+
+    /// Returns whether NLSML output is enabled with `vosk_recognizer_set_nlsml`.
+    #[must_use]
+    pub fn nlsml(&self) -> bool {
+        self.lock().nlsml
+    }
+
+    /// Returns whether the recognizer has a speaker model.
+    #[must_use]
+    pub fn has_speaker_model(&self) -> bool {
+        self.lock().has_speaker_model
+    }
+
+    /// Returns the last value passed to `vosk_recognizer_set_endpointer_mode`, if any.
+    #[cfg(feature = "vosk-0-3-50")]
+    #[must_use]
+    pub fn endpointer_mode(&self) -> Option<crate::VoskEndpointerMode> {
+        self.lock().endpointer_mode
+    }
+
+    /// Returns the last delays passed to `vosk_recognizer_set_endpointer_delays`, if any.
+    #[cfg(feature = "vosk-0-3-50")]
+    #[must_use]
+    pub fn endpointer_delays(&self) -> Option<(f32, f32, f32)> {
+        self.lock().endpointer_delays
+    }
+
+    /// Returns whether the recognizer has been freed.
+    #[must_use]
+    pub fn is_freed(&self) -> bool {
+        self.lock().freed
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RecognizerState> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Accepts `samples`, returning the decoding state and the
+    /// result of the utterance if it was finalized.
+    fn accept(&self, samples: &[f32]) -> (c_int, Option<String>) {
+        let mut state = self.lock();
+        state.samples.extend_from_slice(samples);
+
+        match state.steps.pop_front() {
+            Some(Step::Partial(json)) => {
+                state.partial = Some(json);
+                (0, None)
+            }
+            Some(Step::Finalized(json)) => {
+                state.partial = None;
+                state.result = Some(json.clone());
+                (1, Some(json))
+            }
+            Some(Step::Failed) => (-1, None),
+            None => (0, None),
+        }
+    }
+
+    fn result(&self) -> String {
+        self.lock()
+            .result
+            .take()
+            .unwrap_or_else(|| EMPTY_RESULT.to_owned())
+    }
+
+    fn partial_result(&self) -> String {
+        self.lock()
+            .partial
+            .clone()
+            .unwrap_or_else(|| EMPTY_PARTIAL_RESULT.to_owned())
+    }
+
+    fn take_final_result(&self) -> Option<String> {
+        let mut state = self.lock();
+        state.partial = None;
+        let result = state.result.take();
+        state.final_result.take().or(result)
+    }
+
+    fn reset(&self) {
+        let mut state = self.lock();
+        state.resets += 1;
+        state.partial = None;
+        state.result = None;
+    }
+}
+
+/// Returns a result in the format used by Vosk with `text` as its text.
+#[must_use]
+pub fn text_json(text: &str) -> String {
+    format!(r#"{{"text" : {}}}"#, json_string(text))
+}
+
+/// Returns a partial result in the format used by Vosk with `text` as its text.
+#[must_use]
+pub fn partial_json(text: &str) -> String {
+    format!(r#"{{"partial" : {}}}"#, json_string(text))
+}
+
+/// Quotes and escapes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+
+    json
+}
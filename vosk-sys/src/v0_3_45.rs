@@ -0,0 +1,16 @@
+use crate::VoskRecognizer;
+
+vosk_functions! {
+    v0_3_45,
+    extern "C" {
+        #[doc = " Reconfigures recognizer to use grammar"]
+        #[doc = ""]
+        #[doc = " @param recognizer   Already running VoskRecognizer"]
+        #[doc = " @param grammar      Set of phrases in JSON array of strings or \"\\[\\]\" to use default model graph."]
+        #[doc = "                     See also vosk_recognizer_new_grm"]
+        pub fn vosk_recognizer_set_grm(
+            recognizer: *mut VoskRecognizer,
+            grammar: *const ::std::os::raw::c_char,
+        );
+    }
+}
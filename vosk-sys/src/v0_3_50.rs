@@ -0,0 +1,59 @@
+use crate::VoskRecognizer;
+
+#[doc = " Inverse text normalization"]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct VoskTextProcessor {
+    _unused: [u8; 0],
+}
+
+#[doc = " Default endpointer delays"]
+pub const VoskEndpointerMode_VOSK_EP_ANSWER_DEFAULT: VoskEndpointerMode = 0;
+#[doc = " Shorter delays, for short answers like yes or no"]
+pub const VoskEndpointerMode_VOSK_EP_ANSWER_SHORT: VoskEndpointerMode = 1;
+#[doc = " Longer delays, for answers with pauses"]
+pub const VoskEndpointerMode_VOSK_EP_ANSWER_LONG: VoskEndpointerMode = 2;
+#[doc = " Very long delays, for dictation"]
+pub const VoskEndpointerMode_VOSK_EP_ANSWER_VERY_LONG: VoskEndpointerMode = 3;
+#[doc = " Endpointer modes, which scale the delays used to detect the end of an utterance"]
+pub type VoskEndpointerMode = ::std::os::raw::c_uint;
+
+vosk_functions! {
+    v0_3_50,
+    extern "C" {
+        #[doc = " Set endpointer scaling factor"]
+        #[doc = ""]
+        #[doc = " @param mode Endpointer mode"]
+        pub fn vosk_recognizer_set_endpointer_mode(
+            recognizer: *mut VoskRecognizer,
+            mode: VoskEndpointerMode,
+        );
+
+        #[doc = " Set endpointer delays"]
+        #[doc = ""]
+        #[doc = " @param t_start_max     timeout for stopping recognition in seconds in case of initial silence (usually around 5.0)"]
+        #[doc = " @param t_end           timeout for stopping recognition in seconds after we recognized something (usually around 0.5 - 1.0)"]
+        #[doc = " @param t_max           timeout for forcing utterance end in seconds (usually around 20-30)"]
+        pub fn vosk_recognizer_set_endpointer_delays(
+            recognizer: *mut VoskRecognizer,
+            t_start_max: f32,
+            t_end: f32,
+            t_max: f32,
+        );
+
+        #[doc = " Create text processor"]
+        pub fn vosk_text_processor_new(
+            tagger: *const ::std::os::raw::c_char,
+            verbalizer: *const ::std::os::raw::c_char,
+        ) -> *mut VoskTextProcessor;
+
+        #[doc = " Release text processor"]
+        pub fn vosk_text_processor_free(processor: *mut VoskTextProcessor);
+
+        #[doc = " Convert string"]
+        pub fn vosk_text_processor_itn(
+            processor: *mut VoskTextProcessor,
+            input: *const ::std::os::raw::c_char,
+        ) -> *mut ::std::os::raw::c_char;
+    }
+}
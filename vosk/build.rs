@@ -0,0 +1,17 @@
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=DEP_VOSK_RPATH");
+
+    // The link arguments emitted by vosk-sys only apply to its own targets,
+    // so they are repeated for the examples and tests of this crate
+    if env::var_os("CARGO_FEATURE_RPATH").is_none() {
+        return;
+    }
+    if let Some(rpath) = env::var_os("DEP_VOSK_RPATH") {
+        for dir in env::split_paths(&rpath) {
+            println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
+        }
+    }
+}
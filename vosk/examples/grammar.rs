@@ -9,7 +9,7 @@
 use std::env;
 
 use hound::WavReader;
-use vosk::{DecodingState, Model, Recognizer};
+use vosk::{DecodingState, Grammar, Model, Recognizer};
 
 fn main() {
     let mut args = env::args();
@@ -38,7 +38,7 @@ fn main() {
         // incorrect.
         //
         // Note that the words in a phrase can still be recognized separately
-        &["one two three four five six seven eight nine zero", "[unk]"],
+        &Grammar::from_phrases(["one two three four five six seven eight nine zero", "[unk]"]),
     )
     .expect("Could not create the recognizer");
 
@@ -13,10 +13,11 @@ use std::{
 
 use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
-    ChannelCount, SampleFormat,
+    SampleFormat,
+};
+use vosk::{
+    ChannelLayout, DecodingState, Model, Recognizer, ResamplerQuality, ResamplingRecognizer, Sample,
 };
-use dasp::{sample::ToSample, Sample};
-use vosk::{DecodingState, Model, Recognizer};
 
 fn main() {
     let mut args = env::args();
@@ -37,15 +38,23 @@ fn main() {
     let config = audio_input_device
         .default_input_config()
         .expect("Failed to load default input config");
-    let channels = config.channels();
+    // The recognizer only takes mono audio, so all the channels are averaged into one
+    let layout = ChannelLayout::Downmix {
+        channels: config.channels(),
+    };
 
     let model = Model::new(model_path).expect("Could not create the model");
-    let mut recognizer = Recognizer::new(&model, config.sample_rate().0 as f32)
-        .expect("Could not create the Recognizer");
-
-    recognizer.set_max_alternatives(10);
-    recognizer.set_words(true);
-    recognizer.set_partial_words(true);
+    // The audio from the device is resampled to the rate the model was trained with
+    let mut recognizer = ResamplingRecognizer::new(
+        Recognizer::new(&model, model.sample_rate()).expect("Could not create the Recognizer"),
+        config.sample_rate().0 as f32,
+        ResamplerQuality::Medium,
+    )
+    .expect("Could not create the ResamplingRecognizer");
+
+    recognizer.recognizer_mut().set_max_alternatives(10);
+    recognizer.recognizer_mut().set_words(true);
+    recognizer.recognizer_mut().set_partial_words(true);
 
     let recognizer = Arc::new(Mutex::new(recognizer));
 
@@ -57,17 +66,17 @@ fn main() {
     let stream = match config.sample_format() {
         SampleFormat::F32 => audio_input_device.build_input_stream(
             &config.into(),
-            move |data: &[f32], _| recognize(&mut recognizer_clone.lock().unwrap(), data, channels),
+            move |data: &[f32], _| recognize(&mut recognizer_clone.lock().unwrap(), data, layout),
             err_fn,
         ),
         SampleFormat::U16 => audio_input_device.build_input_stream(
             &config.into(),
-            move |data: &[u16], _| recognize(&mut recognizer_clone.lock().unwrap(), data, channels),
+            move |data: &[u16], _| recognize(&mut recognizer_clone.lock().unwrap(), data, layout),
             err_fn,
         ),
         SampleFormat::I16 => audio_input_device.build_input_stream(
             &config.into(),
-            move |data: &[i16], _| recognize(&mut recognizer_clone.lock().unwrap(), data, channels),
+            move |data: &[i16], _| recognize(&mut recognizer_clone.lock().unwrap(), data, layout),
             err_fn,
         ),
     }
@@ -82,19 +91,8 @@ fn main() {
     println!("{:#?}", recognizer.lock().unwrap().final_result());
 }
 
-fn recognize<T: Sample + ToSample<i16>>(
-    recognizer: &mut Recognizer,
-    data: &[T],
-    channels: ChannelCount,
-) {
-    let data: Vec<i16> = data.iter().map(|v| v.to_sample()).collect();
-    let data = if channels != 1 {
-        stereo_to_mono(&data)
-    } else {
-        data
-    };
-
-    let state = recognizer.accept_waveform(&data);
+fn recognize<T: Sample>(recognizer: &mut ResamplingRecognizer, data: &[T], layout: ChannelLayout) {
+    let state = recognizer.accept_interleaved(data, layout);
     match state {
         DecodingState::Running => {
             println!("partial: {:#?}", recognizer.partial_result());
@@ -106,14 +104,3 @@ fn recognize<T: Sample + ToSample<i16>>(
         DecodingState::Failed => eprintln!("error"),
     }
 }
-
-pub fn stereo_to_mono(input_data: &[i16]) -> Vec<i16> {
-    let mut result = Vec::with_capacity(input_data.len() / 2);
-    result.extend(
-        input_data
-            .chunks_exact(2)
-            .map(|chunk| chunk[0] / 2 + chunk[1] / 2),
-    );
-
-    result
-}
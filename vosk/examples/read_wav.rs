@@ -9,7 +9,7 @@
 use std::env;
 
 use hound::WavReader;
-use vosk::{Model, Recognizer};
+use vosk::{Model, Recognizer, StreamingSession};
 
 fn main() {
     let mut args = env::args();
@@ -34,10 +34,20 @@ fn main() {
     recognizer.set_words(true);
     recognizer.set_partial_words(true);
 
+    // The partial results are only printed when they change
+    let mut session = StreamingSession::new(recognizer)
+        .on_partial(|partial| println!("{partial:#?}"))
+        .on_final(|utterance| {
+            println!("{:?} - {:?}", utterance.start, utterance.end);
+            // Result will always be multiple because we called set_max_alternatives
+            println!("{:#?}", utterance.result.multiple().unwrap());
+        });
+
     for sample in samples.chunks(100) {
-        recognizer.accept_waveform(sample);
-        println!("{:#?}", recognizer.partial_result());
+        session
+            .accept_waveform(sample)
+            .expect("Could not recognize the audio");
     }
 
-    println!("{:#?}", recognizer.final_result().multiple().unwrap());
+    session.finish().expect("Could not recognize the audio");
 }
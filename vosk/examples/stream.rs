@@ -0,0 +1,46 @@
+//! Run with:
+//! cargo run --example stream --features async <model path> <wav path>
+//! e.g. "cargo run --example stream --features async /home/user/stt/model /home/user/stt/test.wav"
+//! (The WAV file must have signed 16-bit samples)
+//!
+//! Read the "Setup" section in the README to know how to link the vosk dynamic
+//! libaries to the examples
+
+use std::env;
+
+use futures::{stream, StreamExt};
+use hound::WavReader;
+use vosk::{Model, RecognitionEvent, Recognizer};
+
+#[tokio::main]
+async fn main() {
+    let mut args = env::args();
+    args.next();
+
+    let model_path = args.next().expect("A model path was not provided");
+    let wav_path = args
+        .next()
+        .expect("A path for the WAV file to be read was not provided");
+
+    let mut reader = WavReader::open(wav_path).expect("Could not create the WAV reader");
+    let samples = reader
+        .samples()
+        .collect::<hound::Result<Vec<i16>>>()
+        .expect("Could not read WAV file");
+
+    let model = Model::new(model_path).expect("Could not create the model");
+    let recognizer = Recognizer::new(&model, reader.spec().sample_rate as f32)
+        .expect("Could not create the recognizer");
+
+    // Any stream of chunks works, like one fed by a microphone or a network connection
+    let audio = stream::iter(samples.chunks(100).map(<[i16]>::to_vec).collect::<Vec<_>>());
+    let mut events = recognizer.into_stream(audio);
+
+    while let Some(event) = events.next().await {
+        match event {
+            RecognitionEvent::Partial(partial) => println!("{partial:#?}"),
+            RecognitionEvent::Final(result) => println!("{result:#?}"),
+            RecognitionEvent::Error(err) => eprintln!("{err}"),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{
+    CompleteResult, DecodingState, EndpointingRecognizer, Error, PartialResult, Recognizer,
+};
+use std::time::Duration;
+
+/// Common interface of the types that turn audio into [results](CompleteResult), so that code
+/// using a recognizer can also run with a different implementation, like a [`ReplayRecognizer`].
+///
+/// The methods behave like the [`Recognizer`] methods with the same name.
+///
+/// [`ReplayRecognizer`]: crate::ReplayRecognizer
+///
+/// # Examples
+///
+/// ```no_run
+/// # use vosk::{DecodingState, Model, Recognizer, SpeechRecognizer};
+/// #
+/// fn transcribe(recognizer: &mut dyn SpeechRecognizer, samples: &[i16]) -> Vec<String> {
+///     let mut texts = Vec::new();
+///     for chunk in samples.chunks(1600) {
+///         if recognizer.accept_waveform(chunk) == DecodingState::Finalized {
+///             texts.extend(recognizer.result().single().map(|result| result.text.into_owned()));
+///         }
+///     }
+///     texts.extend(recognizer.final_result().single().map(|result| result.text.into_owned()));
+///
+///     texts
+/// }
+///
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let mut recognizer =
+///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+/// println!("{:?}", transcribe(&mut recognizer, &vec![0; 16000]));
+/// ```
+pub trait SpeechRecognizer {
+    /// See [`Recognizer::sample_rate`].
+    fn sample_rate(&self) -> f32;
+
+    /// See [`Recognizer::position`].
+    fn position(&self) -> Duration;
+
+    /// See [`Recognizer::accept_waveform`].
+    fn accept_waveform(&mut self, data: &[i16]) -> DecodingState;
+
+    /// See [`Recognizer::accept_waveform_f32`].
+    fn accept_waveform_f32(&mut self, data: &[f32]) -> DecodingState;
+
+    /// See [`Recognizer::try_result`].
+    fn try_result(&mut self) -> Result<CompleteResult<'_>, Error>;
+
+    /// See [`Recognizer::try_partial_result`].
+    fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error>;
+
+    /// See [`Recognizer::try_final_result`].
+    fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error>;
+
+    /// See [`Recognizer::result`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result cannot be parsed.
+    fn result(&mut self) -> CompleteResult<'_> {
+        self.try_result().unwrap()
+    }
+
+    /// See [`Recognizer::partial_result`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result cannot be parsed.
+    fn partial_result(&mut self) -> PartialResult<'_> {
+        self.try_partial_result().unwrap()
+    }
+
+    /// See [`Recognizer::final_result`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result cannot be parsed.
+    fn final_result(&mut self) -> CompleteResult<'_> {
+        self.try_final_result().unwrap()
+    }
+
+    /// See [`Recognizer::reset`].
+    fn reset(&mut self);
+
+    /// See [`Recognizer::set_max_alternatives`].
+    fn set_max_alternatives(&mut self, max_alternatives: u16);
+
+    /// See [`Recognizer::set_words`].
+    fn set_words(&mut self, enable: bool);
+
+    /// See [`Recognizer::set_partial_words`].
+    fn set_partial_words(&mut self, enable: bool);
+}
+
+impl SpeechRecognizer for Recognizer {
+    fn sample_rate(&self) -> f32 {
+        self.sample_rate()
+    }
+
+    fn position(&self) -> Duration {
+        self.position()
+    }
+
+    fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
+        self.accept_waveform(data)
+    }
+
+    fn accept_waveform_f32(&mut self, data: &[f32]) -> DecodingState {
+        self.accept_waveform_f32(data)
+    }
+
+    fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.try_result()
+    }
+
+    fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
+        self.try_partial_result()
+    }
+
+    fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.try_final_result()
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+
+    fn set_max_alternatives(&mut self, max_alternatives: u16) {
+        self.set_max_alternatives(max_alternatives);
+    }
+
+    fn set_words(&mut self, enable: bool) {
+        self.set_words(enable);
+    }
+
+    fn set_partial_words(&mut self, enable: bool) {
+        self.set_partial_words(enable);
+    }
+}
+
+impl SpeechRecognizer for EndpointingRecognizer {
+    fn sample_rate(&self) -> f32 {
+        self.recognizer().sample_rate()
+    }
+
+    fn position(&self) -> Duration {
+        self.recognizer().position()
+    }
+
+    fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
+        self.accept_waveform(data)
+    }
+
+    fn accept_waveform_f32(&mut self, data: &[f32]) -> DecodingState {
+        self.accept_samples(data)
+    }
+
+    fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.try_result()
+    }
+
+    fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
+        self.try_partial_result()
+    }
+
+    fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.try_final_result()
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+
+    fn set_max_alternatives(&mut self, max_alternatives: u16) {
+        self.recognizer_mut().set_max_alternatives(max_alternatives);
+    }
+
+    fn set_words(&mut self, enable: bool) {
+        self.recognizer_mut().set_words(enable);
+    }
+
+    fn set_partial_words(&mut self, enable: bool) {
+        self.recognizer_mut().set_partial_words(enable);
+    }
+}
+
+impl<R: SpeechRecognizer + ?Sized> SpeechRecognizer for Box<R> {
+    fn sample_rate(&self) -> f32 {
+        (**self).sample_rate()
+    }
+
+    fn position(&self) -> Duration {
+        (**self).position()
+    }
+
+    fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
+        (**self).accept_waveform(data)
+    }
+
+    fn accept_waveform_f32(&mut self, data: &[f32]) -> DecodingState {
+        (**self).accept_waveform_f32(data)
+    }
+
+    fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        (**self).try_result()
+    }
+
+    fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
+        (**self).try_partial_result()
+    }
+
+    fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        (**self).try_final_result()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset();
+    }
+
+    fn set_max_alternatives(&mut self, max_alternatives: u16) {
+        (**self).set_max_alternatives(max_alternatives);
+    }
+
+    fn set_words(&mut self, enable: bool) {
+        (**self).set_words(enable);
+    }
+
+    fn set_partial_words(&mut self, enable: bool) {
+        (**self).set_partial_words(enable);
+    }
+}
+
+/// Text of the last partial result that was reported, so that
+/// partial results are only reported when their text changes.
+#[derive(Debug, Default)]
+pub(crate) struct LastPartial(String);
+
+impl LastPartial {
+    /// Returns whether the text of `partial` is different from the last one, and remembers it.
+    pub(crate) fn changed(&mut self, partial: &PartialResult<'_>) -> bool {
+        if partial.partial == self.0 {
+            return false;
+        }
+
+        self.0.clear();
+        self.0.push_str(&partial.partial);
+        true
+    }
+
+    /// Forgets the last partial result, at the end of an utterance.
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+}
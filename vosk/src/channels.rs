@@ -0,0 +1,292 @@
+use crate::{CompleteResult, DecodingState, Error, PartialResult, Recognizer, Sample};
+
+/// Layout of interleaved multichannel audio, and how to turn it into the mono audio
+/// a [`Recognizer`] expects.
+///
+/// Used by [`Recognizer::accept_interleaved`]. To recognize each channel
+/// separately, use a [`MultichannelRecognizer`] instead.
+///
+/// The audio does not need to be split at frame boundaries (a sample for each channel),
+/// like the chunks read from a device or a file. The samples of an incomplete frame at
+/// the end of a chunk are kept, and completed with the start of the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelLayout {
+    /// Averages all the channels into one.
+    Downmix {
+        /// Amount of interleaved channels.
+        channels: u16,
+    },
+
+    /// Only uses one of the channels, discarding the rest.
+    Single {
+        /// Amount of interleaved channels.
+        channels: u16,
+
+        /// Index of the channel to use.
+        channel: u16,
+    },
+}
+
+impl ChannelLayout {
+    /// Returns the amount of interleaved channels.
+    #[must_use]
+    pub fn channels(self) -> u16 {
+        match self {
+            Self::Downmix { channels } | Self::Single { channels, .. } => channels,
+        }
+    }
+
+    /// Returns an iterator over the mono samples of `data`, converted with [`Sample::to_pcm16`].
+    ///
+    /// `partial_frame` holds the samples of the incomplete frame at the end of the previous
+    /// chunk, which are completed with the start of `data`, and it is replaced with the ones
+    /// at the end of `data`. They are discarded if they do not fit in a frame of this layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no channels or the selected channel does not exist.
+    pub(crate) fn to_mono<'a, S: Sample>(
+        self,
+        partial_frame: &mut Vec<f32>,
+        data: &'a [S],
+    ) -> impl Iterator<Item = f32> + 'a {
+        let channels = usize::from(self.channels());
+        assert!(channels > 0, "a channel layout needs at least one channel");
+        if let Self::Single { channel, .. } = self {
+            assert!(
+                usize::from(channel) < channels,
+                "channel {channel} does not exist in a layout with {channels} channels"
+            );
+        }
+
+        if partial_frame.len() >= channels {
+            partial_frame.clear();
+        }
+
+        let mut first_frame = None;
+        let mut data = data;
+        if !partial_frame.is_empty() {
+            let missing = (channels - partial_frame.len()).min(data.len());
+            partial_frame.extend(data[..missing].iter().map(|sample| sample.to_pcm16()));
+            data = &data[missing..];
+
+            if partial_frame.len() == channels {
+                first_frame = Some(self.frame_to_mono(partial_frame.drain(..)));
+            }
+        }
+
+        let complete = data.len() - data.len() % channels;
+        partial_frame.extend(data[complete..].iter().map(|sample| sample.to_pcm16()));
+
+        first_frame.into_iter().chain(
+            data[..complete]
+                .chunks_exact(channels)
+                .map(move |frame| self.frame_to_mono(frame.iter().map(|sample| sample.to_pcm16()))),
+        )
+    }
+
+    /// Turns the samples of a complete frame into a mono sample.
+    fn frame_to_mono(self, mut frame: impl Iterator<Item = f32>) -> f32 {
+        match self {
+            Self::Downmix { channels } => frame.sum::<f32>() / f32::from(channels),
+            Self::Single { channel, .. } => frame
+                .nth(usize::from(channel))
+                .expect("the channel exists in the frame"),
+        }
+    }
+}
+
+/// A value produced by one of the channels of a [`MultichannelRecognizer`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelResult<T> {
+    /// Index of the channel that produced the result.
+    pub channel: u16,
+
+    /// The result itself.
+    pub result: T,
+}
+
+/// Runs a [`Recognizer`] for each channel of interleaved multichannel audio.
+///
+/// Useful when each channel contains a different speaker, like the
+/// agent and the customer on a stereo call recording.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use vosk::{ChannelResult, Model, MultichannelRecognizer, Recognizer};
+/// #
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let mut recognizer = MultichannelRecognizer::new(vec![
+///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer"),
+///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer"),
+/// ])
+/// .expect("Could not create a multichannel recognizer");
+///
+/// // Stereo audio, with interleaved samples for the left and right channels
+/// let samples = vec![0i16; 16000];
+/// for chunk in samples.chunks(4000) {
+///     recognizer.accept_waveform(chunk);
+///
+///     for ChannelResult { channel, result } in recognizer.finalized_results() {
+///         println!("channel {channel}: {result:?}");
+///     }
+/// }
+/// ```
+pub struct MultichannelRecognizer {
+    recognizers: Vec<Recognizer>,
+    states: Vec<DecodingState>,
+
+    /// Amount of recognizers, which always fits in a [`ChannelLayout`].
+    channels: u16,
+}
+
+impl MultichannelRecognizer {
+    /// Creates a multichannel recognizer, with a channel for each recognizer in `recognizers`.
+    ///
+    /// The recognizers are used in order, so the first one will process the first channel.
+    ///
+    /// Returns [`Error::InvalidChannelCount`] if `recognizers` is empty
+    /// or has more recognizers than a [`ChannelLayout`] can have channels.
+    pub fn new(recognizers: Vec<Recognizer>) -> Result<Self, Error> {
+        let channels = u16::try_from(recognizers.len())
+            .ok()
+            .filter(|channels| *channels > 0)
+            .ok_or(Error::InvalidChannelCount(recognizers.len()))?;
+
+        Ok(Self {
+            states: vec![DecodingState::Running; recognizers.len()],
+            recognizers,
+            channels,
+        })
+    }
+
+    /// Returns the amount of channels.
+    #[must_use]
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Returns the recognizer for `channel`, or [`None`] if it does not exist.
+    #[must_use]
+    pub fn recognizer(&self, channel: u16) -> Option<&Recognizer> {
+        self.recognizers.get(usize::from(channel))
+    }
+
+    /// Returns a mutable reference to the recognizer for `channel`,
+    /// or [`None`] if it does not exist.
+    #[must_use]
+    pub fn recognizer_mut(&mut self, channel: u16) -> Option<&mut Recognizer> {
+        self.recognizers.get_mut(usize::from(channel))
+    }
+
+    /// Returns the inner recognizers, ordered by channel.
+    #[must_use]
+    pub fn into_inner(self) -> Vec<Recognizer> {
+        self.recognizers
+    }
+
+    /// Like [`Recognizer::accept_waveform`], but `data` contains interleaved
+    /// samples for all the channels.
+    ///
+    /// Returns the [`DecodingState`] of each channel, ordered by channel.
+    pub fn accept_waveform(&mut self, data: &[i16]) -> &[DecodingState] {
+        self.accept_samples(data)
+    }
+
+    /// Like [`Recognizer::accept_samples`], but `data` contains interleaved
+    /// samples for all the channels.
+    ///
+    /// Returns the [`DecodingState`] of each channel, ordered by channel.
+    pub fn accept_samples<S: Sample>(&mut self, data: &[S]) -> &[DecodingState] {
+        let channels = self.channels;
+
+        // Each recognizer keeps the samples of an incomplete frame at the end of `data`
+        for (channel, (recognizer, state)) in
+            (0..channels).zip(self.recognizers.iter_mut().zip(&mut self.states))
+        {
+            *state =
+                recognizer.accept_interleaved(data, ChannelLayout::Single { channels, channel });
+        }
+
+        &self.states
+    }
+
+    /// Returns the results of the channels that were finalized (see [`DecodingState::Finalized`])
+    /// by the last chunk of data.
+    ///
+    /// See [`Recognizer::result`].
+    #[must_use]
+    pub fn finalized_results(&mut self) -> Vec<ChannelResult<CompleteResult<'_>>> {
+        self.try_finalized_results().unwrap()
+    }
+
+    /// Like [`finalized_results`], but returns the first [`Error`] of the channels
+    /// instead of panicking. See [`Recognizer::try_result`].
+    ///
+    /// [`finalized_results`]: Self::finalized_results
+    pub fn try_finalized_results(
+        &mut self,
+    ) -> Result<Vec<ChannelResult<CompleteResult<'_>>>, Error> {
+        (0..self.channels)
+            .zip(self.recognizers.iter_mut().zip(&self.states))
+            .filter(|(_, (_, state))| **state == DecodingState::Finalized)
+            .map(|(channel, (recognizer, _))| {
+                let result = recognizer.try_result()?;
+                Ok(ChannelResult { channel, result })
+            })
+            .collect()
+    }
+
+    /// Returns the partial results of all the channels.
+    ///
+    /// See [`Recognizer::partial_result`].
+    #[must_use]
+    pub fn partial_results(&mut self) -> Vec<ChannelResult<PartialResult<'_>>> {
+        self.try_partial_results().unwrap()
+    }
+
+    /// Like [`partial_results`], but returns the first [`Error`] of the channels
+    /// instead of panicking. See [`Recognizer::try_partial_result`].
+    ///
+    /// [`partial_results`]: Self::partial_results
+    pub fn try_partial_results(&mut self) -> Result<Vec<ChannelResult<PartialResult<'_>>>, Error> {
+        (0..self.channels)
+            .zip(&mut self.recognizers)
+            .map(|(channel, recognizer)| {
+                let result = recognizer.try_partial_result()?;
+                Ok(ChannelResult { channel, result })
+            })
+            .collect()
+    }
+
+    /// Returns the final results of all the channels.
+    ///
+    /// See [`Recognizer::final_result`].
+    #[must_use]
+    pub fn final_results(&mut self) -> Vec<ChannelResult<CompleteResult<'_>>> {
+        self.try_final_results().unwrap()
+    }
+
+    /// Like [`final_results`], but returns the first [`Error`] of the channels
+    /// instead of panicking. See [`Recognizer::try_final_result`].
+    ///
+    /// [`final_results`]: Self::final_results
+    pub fn try_final_results(&mut self) -> Result<Vec<ChannelResult<CompleteResult<'_>>>, Error> {
+        (0..self.channels)
+            .zip(&mut self.recognizers)
+            .map(|(channel, recognizer)| {
+                let result = recognizer.try_final_result()?;
+                Ok(ChannelResult { channel, result })
+            })
+            .collect()
+    }
+
+    /// Resets all the recognizers. See [`Recognizer::reset`].
+    pub fn reset(&mut self) {
+        for recognizer in &mut self.recognizers {
+            recognizer.reset();
+        }
+        self.states.fill(DecodingState::Running);
+    }
+}
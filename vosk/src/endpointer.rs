@@ -0,0 +1,291 @@
+use crate::{
+    ChannelLayout, CompleteResult, DecodingState, Error, PartialResult, Recognizer, Sample,
+};
+use std::time::Duration;
+
+/// Preset for the delays used to detect the end of an utterance.
+///
+/// Used by [`Recognizer::set_endpointer_mode`] and [`EndpointerDelays::from_mode`].
+///
+/// [`Recognizer::set_endpointer_mode`]: crate::Recognizer::set_endpointer_mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EndpointerMode {
+    /// The default delays, suited for most conversations.
+    #[default]
+    Default,
+
+    /// Shorter delays, for short answers like "yes" or "no" and commands.
+    Short,
+
+    /// Longer delays, for answers that contain pauses.
+    Long,
+
+    /// Even longer delays, for dictation.
+    VeryLong,
+}
+
+/// Delays used to detect the end of an utterance.
+///
+/// Used by [`Recognizer::set_endpointer_delays`] and [`EndpointingRecognizer`].
+///
+/// [`Recognizer::set_endpointer_delays`]: crate::Recognizer::set_endpointer_delays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointerDelays {
+    /// Silence after which the utterance ends if nothing has been recognized yet.
+    pub start_max: Duration,
+
+    /// Silence after which the utterance ends once something has been recognized.
+    pub end: Duration,
+
+    /// Maximum length of an utterance, after which it ends even if the speaker has not stopped.
+    pub max: Duration,
+}
+
+impl EndpointerDelays {
+    /// Returns the delays for `mode`.
+    ///
+    /// The default delays are those of Kaldi's default endpointing rules. [`EndpointerMode::Short`]
+    /// halves the delay for the end of speech, [`EndpointerMode::Long`] doubles it and
+    /// [`EndpointerMode::VeryLong`] triples it, like Vosk scales its trailing silence for each
+    /// preset. These factors are not read from Vosk, which does not expose the values it uses,
+    /// so they are an approximation of [`Recognizer::set_endpointer_mode`] and may not match it.
+    ///
+    /// [`Recognizer::set_endpointer_mode`]: crate::Recognizer::set_endpointer_mode
+    #[must_use]
+    pub fn from_mode(mode: EndpointerMode) -> Self {
+        let scale = match mode {
+            EndpointerMode::Default => 1.0,
+            EndpointerMode::Short => 0.5,
+            EndpointerMode::Long => 2.0,
+            EndpointerMode::VeryLong => 3.0,
+        };
+
+        Self {
+            start_max: Duration::from_secs(5),
+            end: Duration::from_millis(500).mul_f32(scale),
+            max: Duration::from_secs(20),
+        }
+    }
+}
+
+impl Default for EndpointerDelays {
+    fn default() -> Self {
+        Self::from_mode(EndpointerMode::Default)
+    }
+}
+
+/// Wraps a [`Recognizer`] to end utterances after configurable [`EndpointerDelays`],
+/// without needing a version of Vosk that supports [`Recognizer::set_endpointer_delays`].
+///
+/// The silence is measured from the end of the last word of the partial result to the
+/// [position](Recognizer::position) of the recognizer, so partial words are enabled on the inner
+/// recognizer (see [`Recognizer::set_partial_words`]). The utterance is ended with
+/// [`Recognizer::final_result`] once the silence lasts for [`EndpointerDelays::end`].
+/// The results of the inner recognizer are checked after each chunk of audio,
+/// so smaller chunks make the delays more precise.
+///
+/// Utterances can still be finalized earlier by Vosk's own endpointer.
+///
+/// [`Recognizer::set_endpointer_delays`]: crate::Recognizer::set_endpointer_delays
+///
+/// # Examples
+///
+/// ```no_run
+/// # use vosk::{DecodingState, EndpointerDelays, EndpointerMode, EndpointingRecognizer, Model, Recognizer};
+/// #
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let recognizer =
+///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+/// let mut recognizer =
+///     EndpointingRecognizer::new(recognizer, EndpointerDelays::from_mode(EndpointerMode::Short));
+///
+/// let samples = vec![0i16; 16000];
+/// for chunk in samples.chunks(1600) {
+///     if recognizer.accept_waveform(chunk) == DecodingState::Finalized {
+///         println!("{:?}", recognizer.result());
+///     }
+/// }
+/// ```
+pub struct EndpointingRecognizer {
+    recognizer: Recognizer,
+    delays: EndpointerDelays,
+
+    /// Result of the utterance that was ended by the endpointer with the last chunk,
+    /// if it has not been retrieved yet.
+    ended_result: Option<Result<CompleteResult<'static>, Error>>,
+
+    /// Position of the recognizer when the utterance started.
+    utterance_start: Duration,
+}
+
+impl EndpointingRecognizer {
+    /// Wraps `recognizer` so that its utterances end after `delays`, enabling its partial words.
+    #[must_use]
+    pub fn new(mut recognizer: Recognizer, delays: EndpointerDelays) -> Self {
+        recognizer.set_partial_words(true);
+        let utterance_start = recognizer.position();
+
+        Self {
+            recognizer,
+            delays,
+            ended_result: None,
+            utterance_start,
+        }
+    }
+
+    /// Returns the delays used to end utterances.
+    #[must_use]
+    pub fn delays(&self) -> EndpointerDelays {
+        self.delays
+    }
+
+    /// Changes the delays used to end utterances, including the current one.
+    pub fn set_delays(&mut self, delays: EndpointerDelays) {
+        self.delays = delays;
+    }
+
+    /// Returns a reference to the inner [`Recognizer`].
+    #[must_use]
+    pub fn recognizer(&self) -> &Recognizer {
+        &self.recognizer
+    }
+
+    /// Returns a mutable reference to the inner [`Recognizer`], which can be used to configure it.
+    ///
+    /// Audio fed directly to it is only taken into account by the endpointer with the next chunk,
+    /// and the endpointer cannot detect the end of speech if partial words are disabled.
+    #[must_use]
+    pub fn recognizer_mut(&mut self) -> &mut Recognizer {
+        &mut self.recognizer
+    }
+
+    /// Returns the inner [`Recognizer`], discarding the result of an
+    /// utterance ended by the endpointer if it has not been retrieved.
+    ///
+    /// Its partial words are left enabled.
+    #[must_use]
+    pub fn into_inner(self) -> Recognizer {
+        self.recognizer
+    }
+
+    /// Like [`Recognizer::accept_waveform`], but [`DecodingState::Finalized`] is
+    /// also returned when the endpointer ends the utterance.
+    pub fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
+        self.accept_samples(data)
+    }
+
+    /// Like [`Recognizer::accept_samples`], but [`DecodingState::Finalized`] is
+    /// also returned when the endpointer ends the utterance.
+    pub fn accept_samples<S: Sample>(&mut self, data: &[S]) -> DecodingState {
+        let state = self.recognizer.accept_samples(data);
+        self.update(state)
+    }
+
+    /// Like [`Recognizer::accept_interleaved`], but [`DecodingState::Finalized`] is
+    /// also returned when the endpointer ends the utterance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout` has no channels or selects a channel that does not exist.
+    pub fn accept_interleaved<S: Sample>(
+        &mut self,
+        data: &[S],
+        layout: ChannelLayout,
+    ) -> DecodingState {
+        let state = self.recognizer.accept_interleaved(data, layout);
+        self.update(state)
+    }
+
+    /// Ends the utterance if any of the delays has passed after a chunk was accepted.
+    fn update(&mut self, state: DecodingState) -> DecodingState {
+        // A result that was not retrieved after the previous chunk would otherwise
+        // be returned instead of the one of this chunk
+        self.ended_result = None;
+
+        if state != DecodingState::Running {
+            self.start_utterance();
+            return state;
+        }
+
+        // The times of the words, like the position, are counted from the first sample fed
+        // to the recognizer. Errors are handled when the user retrieves the results, here
+        // they are treated as if nothing had been said
+        let speech_end = self
+            .recognizer
+            .try_partial_result()
+            .ok()
+            .and_then(|partial| partial.partial_result.last().map(|word| word.end))
+            .and_then(|end| Duration::try_from_secs_f32(end).ok());
+
+        let position = self.recognizer.position();
+        let ended = match speech_end {
+            Some(end) => position.saturating_sub(end.max(self.utterance_start)) >= self.delays.end,
+            None => position.saturating_sub(self.utterance_start) >= self.delays.start_max,
+        };
+
+        if ended || position.saturating_sub(self.utterance_start) >= self.delays.max {
+            self.ended_result = Some(
+                self.recognizer
+                    .try_final_result()
+                    .map(CompleteResult::into_owned),
+            );
+            self.start_utterance();
+
+            DecodingState::Finalized
+        } else {
+            DecodingState::Running
+        }
+    }
+
+    fn start_utterance(&mut self) {
+        self.utterance_start = self.recognizer.position();
+    }
+
+    /// Like [`Recognizer::result`], but it returns the result of the utterance
+    /// ended by the endpointer with the last chunk if there is one.
+    #[must_use]
+    pub fn result(&mut self) -> CompleteResult<'_> {
+        self.try_result().unwrap()
+    }
+
+    /// Like [`Recognizer::try_result`], but it returns the result of the utterance
+    /// ended by the endpointer with the last chunk if there is one.
+    pub fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        match self.ended_result.take() {
+            Some(result) => result,
+            None => self.recognizer.try_result(),
+        }
+    }
+
+    /// See [`Recognizer::partial_result`].
+    #[must_use]
+    pub fn partial_result(&mut self) -> PartialResult<'_> {
+        self.recognizer.partial_result()
+    }
+
+    /// See [`Recognizer::try_partial_result`].
+    pub fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
+        self.recognizer.try_partial_result()
+    }
+
+    /// See [`Recognizer::final_result`].
+    #[must_use]
+    pub fn final_result(&mut self) -> CompleteResult<'_> {
+        self.try_final_result().unwrap()
+    }
+
+    /// See [`Recognizer::try_final_result`].
+    pub fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.ended_result = None;
+        self.start_utterance();
+        self.recognizer.try_final_result()
+    }
+
+    /// Like [`Recognizer::reset`], but it also discards the result
+    /// of an utterance ended by the endpointer.
+    pub fn reset(&mut self) {
+        self.ended_result = None;
+        self.start_utterance();
+        self.recognizer.reset();
+    }
+}
@@ -0,0 +1,203 @@
+use crate::grammar::OutOfVocabulary;
+use std::{fmt, io, path::PathBuf};
+
+/// Errors that can occur when interacting with Vosk.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The given path does not exist, or is not a directory when a model directory is expected.
+    PathNotFound(PathBuf),
+
+    /// The given path cannot be passed to Vosk, either because it
+    /// contains an interior NUL byte or because it is not valid UTF-8.
+    InvalidPath(PathBuf),
+
+    /// A file that Vosk requires to load the model is missing from the model directory.
+    MissingModelFile {
+        /// Path of the missing file, relative to the model directory.
+        file: PathBuf,
+    },
+
+    /// A configuration file in the model directory could not be read or contains an invalid value.
+    InvalidModelConfig {
+        /// Path of the configuration file, relative to the model directory.
+        file: PathBuf,
+
+        /// Description of the problem.
+        reason: String,
+    },
+
+    /// Vosk failed to initialize the object even though all pre-flight checks passed.
+    /// This usually means that one of the model files is corrupt.
+    ///
+    /// Check Kaldi's log output for more information.
+    NativeInitFailed,
+
+    /// The sample rate is not a finite, positive number.
+    InvalidSampleRate(f32),
+
+    /// The amount of channels is zero, or more than [`u16::MAX`].
+    InvalidChannelCount(usize),
+
+    /// The grammar cannot be passed to Vosk because it contains an interior NUL byte.
+    InvalidGrammar,
+
+    /// The model cannot be restricted to a grammar because it has a precompiled static graph.
+    ///
+    /// See [`ModelCapabilities::supports_runtime_grammar`](crate::ModelCapabilities::supports_runtime_grammar).
+    GrammarNotSupported,
+
+    /// Some phrases in a grammar contain words that the model cannot recognize.
+    ///
+    /// Returned by [`Grammar::validate`](crate::Grammar::validate).
+    OutOfVocabulary(Vec<OutOfVocabulary>),
+
+    /// A text grammar, like a [JSGF](crate::grammar::jsgf) one, is not valid.
+    GrammarParse {
+        /// Line where the problem was found, starting at 1.
+        line: usize,
+
+        /// Column where the problem was found, starting at 1.
+        column: usize,
+
+        /// Description of the problem.
+        message: String,
+    },
+
+    /// A grammar cannot be expanded into a list of phrases, because it references
+    /// a rule that does not exist, is recursive or expands to too many phrases.
+    GrammarExpansion(String),
+
+    /// Vosk could not decode a chunk of audio (see [`DecodingState::Failed`]).
+    ///
+    /// [`DecodingState::Failed`]: crate::DecodingState::Failed
+    DecodingFailed,
+
+    /// Vosk could not normalize a text (see [`TextProcessor::itn`]).
+    ///
+    /// [`TextProcessor::itn`]: crate::TextProcessor::itn
+    NormalizationFailed,
+
+    /// A JSON result returned by Vosk is not valid UTF-8.
+    InvalidResultUtf8 {
+        /// Raw result, with invalid sequences replaced by `U+FFFD`.
+        json: String,
+    },
+
+    /// A result returned by Vosk does not have the expected JSON shape.
+    InvalidResultJson {
+        /// Raw result as returned by Vosk.
+        json: String,
+
+        /// Error returned by the JSON parser.
+        source: serde_json::Error,
+    },
+
+    /// A result returned by Vosk is not a valid NLSML document.
+    InvalidResultNlsml {
+        /// Raw result as returned by Vosk.
+        xml: String,
+
+        /// Description of the problem.
+        reason: String,
+    },
+
+    /// A recording of results played back by a [`ReplayRecognizer`] is not valid, or
+    /// the results could not be retrieved to record them with a [`RecordingRecognizer`].
+    ///
+    /// [`ReplayRecognizer`]: crate::ReplayRecognizer
+    /// [`RecordingRecognizer`]: crate::RecordingRecognizer
+    InvalidRecording {
+        /// Line of the recording where the problem was found, starting at 1.
+        line: usize,
+
+        /// Description of the problem.
+        reason: String,
+    },
+
+    /// A file could not be read or written.
+    Io(io::Error),
+
+    /// The Vosk library could not be loaded at runtime.
+    ///
+    /// Only returned with the `dynamic-load` feature, see [`library`](crate::library).
+    #[cfg(feature = "dynamic-load")]
+    Library(crate::library::LoadError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PathNotFound(path) => {
+                write!(f, "path {} does not exist", path.display())
+            }
+            Self::InvalidPath(path) => write!(
+                f,
+                "path {} contains a NUL byte or is not valid UTF-8",
+                path.display()
+            ),
+            Self::MissingModelFile { file } => {
+                write!(f, "model file {} is missing", file.display())
+            }
+            Self::InvalidModelConfig { file, reason } => {
+                write!(
+                    f,
+                    "invalid model configuration in {} ({reason})",
+                    file.display()
+                )
+            }
+            Self::NativeInitFailed => f.write_str("vosk failed to initialize the object"),
+            Self::InvalidSampleRate(sample_rate) => {
+                write!(f, "invalid sample rate {sample_rate}")
+            }
+            Self::InvalidChannelCount(channels) => {
+                write!(f, "invalid amount of channels {channels}")
+            }
+            Self::InvalidGrammar => f.write_str("grammar contains a NUL byte"),
+            Self::GrammarNotSupported => f.write_str(
+                "model has a static graph (HCLG.fst) and does not support runtime grammars",
+            ),
+            Self::OutOfVocabulary(phrases) => {
+                f.write_str("grammar contains words that are not in the model's vocabulary:")?;
+                for OutOfVocabulary { phrase, words } in phrases {
+                    write!(f, " {:?} in {phrase:?}", words.join(" "))?;
+                }
+
+                Ok(())
+            }
+            Self::GrammarParse {
+                line,
+                column,
+                message,
+            } => write!(f, "invalid grammar at {line}:{column}: {message}"),
+            Self::GrammarExpansion(reason) => write!(f, "could not expand grammar: {reason}"),
+            Self::DecodingFailed => f.write_str("vosk could not decode the audio"),
+            Self::NormalizationFailed => f.write_str("vosk could not normalize the text"),
+            Self::InvalidResultUtf8 { json } => write!(f, "result is not valid UTF-8: {json}"),
+            Self::InvalidResultJson { json, source } => {
+                write!(f, "could not parse result ({source}): {json}")
+            }
+            Self::InvalidResultNlsml { xml, reason } => {
+                write!(f, "could not parse NLSML result ({reason}): {xml}")
+            }
+            Self::InvalidRecording { line, reason } => {
+                write!(f, "invalid recording at line {line}: {reason}")
+            }
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            #[cfg(feature = "dynamic-load")]
+            Self::Library(err) => write!(f, "could not load the Vosk library: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidResultJson { source, .. } => Some(source),
+            Self::Io(err) => Some(err),
+            #[cfg(feature = "dynamic-load")]
+            Self::Library(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Captions in the [SubRip](https://en.wikipedia.org/wiki/SubRip) (SRT) and
+//! [WebVTT](https://www.w3.org/TR/webvtt1/) formats, built from the words of recognition results.
+//!
+//! The words are grouped into [`Cue`]s following [`CaptionOptions`], so the results
+//! need to include them (see [`Recognizer::set_words`]).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use vosk::{export::{CaptionOptions, Captions}, Model, Recognizer};
+//! #
+//! let model = Model::new("/path/to/model").expect("Could not create a model");
+//! let mut recognizer =
+//!     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+//! recognizer.set_words(true);
+//!
+//! let mut results = Vec::new();
+//! for chunk in vec![0i16; 160000].chunks(1600) {
+//!     if recognizer.accept_waveform(chunk) == vosk::DecodingState::Finalized {
+//!         results.push(recognizer.result().single().unwrap().into_owned());
+//!     }
+//! }
+//! results.push(recognizer.final_result().single().unwrap().into_owned());
+//!
+//! let captions = Captions::from_results(&results, &CaptionOptions::default());
+//! std::fs::write("captions.srt", captions.to_srt()).expect("Could not write the captions");
+//! ```
+//!
+//! [`Recognizer::set_words`]: crate::Recognizer::set_words
+
+use crate::{CompleteResultSingle, TimedWord, Timeline};
+use std::{fmt::Write as _, io, time::Duration};
+
+/// Class of the WebVTT spans of words with a low confidence, see [`CaptionOptions::low_confidence`].
+///
+/// They can be styled with the `::cue(.low-confidence)` CSS selector.
+pub const LOW_CONFIDENCE_CLASS: &str = "low-confidence";
+
+/// How the words are split into [`Cue`]s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptionOptions {
+    /// Maximum amount of characters in each line of a cue. A word that is longer
+    /// than it on its own is put on a line by itself instead of being broken.
+    pub max_line_length: usize,
+
+    /// Maximum amount of lines in a cue.
+    pub max_lines: usize,
+
+    /// Maximum time a cue is shown for. A word that is longer
+    /// than it on its own is put in a cue by itself.
+    pub max_cue_duration: Duration,
+
+    /// Silence between two words after which a new cue is started, so that captions are not
+    /// shown while nobody is speaking. [`None`] to only split cues when they are full.
+    pub split_on_pause: Option<Duration>,
+
+    /// Confidence under which words are wrapped in a span of the [`LOW_CONFIDENCE_CLASS`]
+    /// class in WebVTT captions. [`None`] to not style any words. SRT captions are not styled.
+    pub low_confidence: Option<f32>,
+}
+
+impl Default for CaptionOptions {
+    fn default() -> Self {
+        Self {
+            max_line_length: 42,
+            max_lines: 2,
+            max_cue_duration: Duration::from_secs(7),
+            split_on_pause: Some(Duration::from_millis(700)),
+            low_confidence: None,
+        }
+    }
+}
+
+/// A caption, shown from [`start`](Self::start) to [`end`](Self::end).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue<'a> {
+    /// Offset in the stream where the first word starts.
+    pub start: Duration,
+
+    /// Offset in the stream where the last word ends.
+    pub end: Duration,
+
+    /// Words of each line of the cue, which is never empty.
+    pub lines: Vec<Vec<TimedWord<'a>>>,
+}
+
+impl<'a> Cue<'a> {
+    /// Starts a cue with `word` on its first line.
+    fn new(word: TimedWord<'a>) -> Self {
+        Self {
+            start: word.start,
+            end: word.end,
+            lines: vec![vec![word]],
+        }
+    }
+
+    /// Returns the text of each line, with the words separated by spaces.
+    #[must_use]
+    pub fn text_lines(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|word| word.word.as_ref())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    /// Adds `word` to the cue if it fits in it according to `options`,
+    /// or returns it back otherwise.
+    fn push(&mut self, word: TimedWord<'a>, options: &CaptionOptions) -> Result<(), TimedWord<'a>> {
+        let paused = options
+            .split_on_pause
+            .is_some_and(|pause| word.start.saturating_sub(self.end) >= pause);
+        let too_long = word.end.saturating_sub(self.start) > options.max_cue_duration;
+        if paused || too_long {
+            return Err(word);
+        }
+
+        let end = word.end;
+        // Cannot be empty, cues are created with a word
+        let line = self.lines.last_mut().unwrap();
+        let length: usize = line.iter().map(|word| word.word.chars().count() + 1).sum();
+        if length + word.word.chars().count() <= options.max_line_length {
+            line.push(word);
+        } else if self.lines.len() < options.max_lines {
+            self.lines.push(vec![word]);
+        } else {
+            return Err(word);
+        }
+
+        self.end = self.end.max(end);
+        Ok(())
+    }
+}
+
+/// Captions for a stream of audio, made of [`Cue`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Captions<'a> {
+    cues: Vec<Cue<'a>>,
+    options: CaptionOptions,
+}
+
+impl<'a> Captions<'a> {
+    /// Splits `words`, which must be in order, into cues.
+    ///
+    /// Use [`CompleteResultSingle::timed_words`] to get the words of results with their times
+    /// in the whole stream, like for a recognizer that started partway through it (see [`Timeline`]).
+    #[must_use]
+    pub fn new(words: impl IntoIterator<Item = TimedWord<'a>>, options: &CaptionOptions) -> Self {
+        let mut captions = Self::empty(options);
+        captions.push_words(words);
+        captions
+    }
+
+    /// Splits the words of `results`, which must be in order, into cues.
+    ///
+    /// Each result is an utterance, so cues never span more than one of them.
+    /// The times of the words are used as reported by Vosk, see [`Captions::new`]
+    /// for results of a recognizer that started partway through the stream.
+    #[must_use]
+    pub fn from_results<'r: 'a>(
+        results: impl IntoIterator<Item = &'a CompleteResultSingle<'r>>,
+        options: &CaptionOptions,
+    ) -> Self {
+        let timeline = Timeline::default();
+        let mut captions = Self::empty(options);
+        for result in results {
+            captions.push_words(result.timed_words(&timeline));
+        }
+
+        captions
+    }
+
+    fn empty(options: &CaptionOptions) -> Self {
+        Self {
+            cues: Vec::new(),
+            options: *options,
+        }
+    }
+
+    /// Adds the cues for `words`, starting a new one for the first word.
+    fn push_words(&mut self, words: impl IntoIterator<Item = TimedWord<'a>>) {
+        let mut cue: Option<Cue<'a>> = None;
+        for word in words {
+            let word = match &mut cue {
+                Some(current) => match current.push(word, &self.options) {
+                    Ok(()) => continue,
+                    Err(word) => word,
+                },
+                None => word,
+            };
+
+            self.cues.extend(cue.replace(Cue::new(word)));
+        }
+
+        self.cues.extend(cue);
+    }
+
+    /// Returns the cues of the captions.
+    #[must_use]
+    pub fn cues(&self) -> &[Cue<'a>] {
+        &self.cues
+    }
+
+    /// Formats the captions as SRT.
+    #[must_use]
+    pub fn to_srt(&self) -> String {
+        let mut srt = String::new();
+        for (index, cue) in self.cues.iter().enumerate() {
+            if index > 0 {
+                srt.push('\n');
+            }
+
+            let _ = writeln!(
+                srt,
+                "{}\n{} --> {}",
+                index + 1,
+                timestamp(cue.start, ','),
+                timestamp(cue.end, ',')
+            );
+            for line in cue.text_lines() {
+                let _ = writeln!(srt, "{line}");
+            }
+        }
+
+        srt
+    }
+
+    /// Formats the captions as WebVTT, styling the words with a
+    /// low confidence as set by [`CaptionOptions::low_confidence`].
+    #[must_use]
+    pub fn to_vtt(&self) -> String {
+        let mut vtt = String::from("WEBVTT\n");
+        for cue in &self.cues {
+            let _ = writeln!(
+                vtt,
+                "\n{} --> {}",
+                timestamp(cue.start, '.'),
+                timestamp(cue.end, '.')
+            );
+            for line in &cue.lines {
+                vtt_line(&mut vtt, line, self.options.low_confidence);
+            }
+        }
+
+        vtt
+    }
+
+    /// Writes the captions as SRT to `writer`, see [`to_srt`](Self::to_srt).
+    pub fn write_srt(&self, mut writer: impl io::Write) -> io::Result<()> {
+        writer.write_all(self.to_srt().as_bytes())
+    }
+
+    /// Writes the captions as WebVTT to `writer`, see [`to_vtt`](Self::to_vtt).
+    pub fn write_vtt(&self, mut writer: impl io::Write) -> io::Result<()> {
+        writer.write_all(self.to_vtt().as_bytes())
+    }
+}
+
+/// Formats `offset` as `hh:mm:ss` followed by the milliseconds after `separator`.
+fn timestamp(offset: Duration, separator: char) -> String {
+    // Rounded, as the times reported by Vosk are not exact after being converted from f32
+    let millis = (offset.as_nanos() + 500_000) / 1_000_000;
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Writes a line of WebVTT cue text, wrapping the runs of words
+/// with a confidence lower than `low_confidence` in a class span.
+fn vtt_line(vtt: &mut String, line: &[TimedWord<'_>], low_confidence: Option<f32>) {
+    let is_low = |word: &TimedWord<'_>| {
+        low_confidence
+            .zip(word.conf)
+            .is_some_and(|(threshold, conf)| conf < threshold)
+    };
+
+    let mut in_span = false;
+    for (index, word) in line.iter().enumerate() {
+        let low = is_low(word);
+        if in_span && !low {
+            vtt.push_str("</c>");
+            in_span = false;
+        }
+        if index > 0 {
+            vtt.push(' ');
+        }
+        if low && !in_span {
+            let _ = write!(vtt, "<c.{LOW_CONFIDENCE_CLASS}>");
+            in_span = true;
+        }
+
+        for c in word.word.chars() {
+            match c {
+                '&' => vtt.push_str("&amp;"),
+                '<' => vtt.push_str("&lt;"),
+                '>' => vtt.push_str("&gt;"),
+                c => vtt.push(c),
+            }
+        }
+    }
+
+    if in_span {
+        vtt.push_str("</c>");
+    }
+    vtt.push('\n');
+}
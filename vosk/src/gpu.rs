@@ -0,0 +1,29 @@
+use vosk_sys::*;
+
+/// Initializes CUDA, automatically selecting a device and allowing multithreading.
+///
+/// Must be called once from the main thread. Has no effect if Vosk was not built with CUDA support.
+///
+/// # Panics
+///
+/// With the `dynamic-load` feature, panics if the Vosk library cannot be loaded.
+pub fn gpu_init() {
+    #[cfg(feature = "dynamic-load")]
+    crate::library::expect_loaded();
+
+    unsafe { vosk_gpu_init() }
+}
+
+/// Initializes the CUDA device in a multi-threaded environment.
+///
+/// Must be called for each thread. Has no effect if Vosk was not built with CUDA support.
+///
+/// # Panics
+///
+/// With the `dynamic-load` feature, panics if the Vosk library cannot be loaded.
+pub fn gpu_thread_init() {
+    #[cfg(feature = "dynamic-load")]
+    crate::library::expect_loaded();
+
+    unsafe { vosk_gpu_thread_init() }
+}
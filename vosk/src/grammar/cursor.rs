@@ -0,0 +1,247 @@
+use crate::Error;
+
+/// Position in the source of a grammar, used by the text grammar parsers.
+pub(crate) struct Cursor<'a> {
+    source: &'a str,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        Self::at(source, 1, 1)
+    }
+
+    /// Creates a cursor for `source`, which starts at `line` and `column` of
+    /// a bigger document, so that errors point to the right position in it.
+    pub(crate) fn at(source: &'a str, line: usize, column: usize) -> Self {
+        Self {
+            source,
+            position: 0,
+            line,
+            column,
+        }
+    }
+
+    /// Returns the text that has not been consumed yet.
+    pub(crate) fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    pub(crate) fn is_eof(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub(crate) fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    /// Consumes `c` if it is the next character.
+    pub(crate) fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes `c`, returning an error if it is not the next character.
+    pub(crate) fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("{c:?}")))
+        }
+    }
+
+    /// Consumes `keyword` if it is the next word.
+    pub(crate) fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is_keyword = self.rest().strip_prefix(keyword).is_some_and(|rest| {
+            !rest
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        });
+
+        if is_keyword {
+            keyword.chars().for_each(|_| {
+                self.bump();
+            });
+        }
+
+        is_keyword
+    }
+
+    /// Consumes characters while `predicate` returns `true`, and returns them.
+    pub(crate) fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+
+        &self.source[start..self.position]
+    }
+
+    /// Consumes characters until `end`, which is consumed but not returned.
+    pub(crate) fn take_until(&mut self, end: char) -> Result<&'a str, Error> {
+        let text = self.take_while(|c| c != end);
+        self.expect(end)?;
+
+        Ok(text)
+    }
+
+    /// Consumes characters until `end`, which is consumed but not returned.
+    pub(crate) fn take_until_str(&mut self, end: &str) -> Result<&'a str, Error> {
+        let start = self.position;
+        while !self.rest().starts_with(end) {
+            if self.bump().is_none() {
+                return Err(self.unexpected(&format!("{end:?}")));
+            }
+        }
+
+        let text = &self.source[start..self.position];
+        end.chars().for_each(|_| {
+            self.bump();
+        });
+
+        Ok(text)
+    }
+
+    /// Returns the line and column of the current position, starting at 1.
+    pub(crate) fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    /// Skips whitespace and C-style comments.
+    pub(crate) fn skip_trivia(&mut self) {
+        loop {
+            self.take_while(char::is_whitespace);
+
+            if self.rest().starts_with("//") {
+                self.take_while(|c| c != '\n');
+            } else if self.rest().starts_with("/*") {
+                self.bump();
+                self.bump();
+                while !self.is_eof() && !self.rest().starts_with("*/") {
+                    self.bump();
+                }
+                self.bump();
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns a parse error at the current position.
+    pub(crate) fn error(&self, message: impl Into<String>) -> Error {
+        Error::GrammarParse {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    /// Returns an error saying that `expected` was expected at the current position.
+    pub(crate) fn unexpected(&self, expected: &str) -> Error {
+        match self.peek() {
+            Some(c) => self.error(format!("expected {expected}, found {c:?}")),
+            None => self.error(format!("expected {expected}, found the end of the grammar")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Debug;
+
+    fn error(result: Result<impl Debug, Error>) -> (usize, usize, String) {
+        match result {
+            Err(Error::GrammarParse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn positions_count_characters_and_lines() {
+        let mut cursor = Cursor::new("héllo\nwörld");
+        assert_eq!(cursor.take_while(|c| c != '\n'), "héllo");
+        assert_eq!(cursor.position(), (1, 6));
+
+        assert_eq!(cursor.bump(), Some('\n'));
+        assert_eq!(cursor.position(), (2, 1));
+        assert_eq!(cursor.take_until('l').unwrap(), "wör");
+        assert_eq!(cursor.position(), (2, 5));
+        assert_eq!(cursor.rest(), "d");
+
+        // Positions are offset for sources embedded in bigger documents
+        let mut cursor = Cursor::at("a\nb", 3, 10);
+        cursor.bump();
+        assert_eq!(cursor.position(), (3, 11));
+        cursor.bump();
+        assert_eq!(cursor.position(), (4, 1));
+    }
+
+    #[test]
+    fn keywords_need_a_word_boundary() {
+        let mut cursor = Cursor::new("publicity public<a>");
+        assert!(!cursor.eat_keyword("public"));
+        assert!(cursor.eat_keyword("publicity"));
+
+        cursor.skip_trivia();
+        assert!(cursor.eat_keyword("public"));
+        assert_eq!(cursor.rest(), "<a>");
+        assert!(!cursor.eat_keyword("public"));
+    }
+
+    #[test]
+    fn trivia_is_skipped() {
+        let mut cursor = Cursor::new("  // line\n /* block\n */ \t x /* unterminated");
+        cursor.skip_trivia();
+        assert_eq!(cursor.position(), (3, 7));
+        assert!(cursor.eat('x'));
+
+        cursor.skip_trivia();
+        assert!(cursor.is_eof());
+    }
+
+    #[test]
+    fn errors_point_to_the_current_position() {
+        let mut cursor = Cursor::new("ab\ncd");
+        assert_eq!(cursor.take_until_str("\nc").unwrap(), "ab");
+        assert_eq!(
+            error(cursor.expect(';')),
+            (2, 2, "expected ';', found 'd'".to_owned())
+        );
+
+        assert_eq!(
+            error(cursor.take_until_str("*/")),
+            (
+                2,
+                3,
+                r#"expected "*/", found the end of the grammar"#.to_owned()
+            )
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use super::{ExpansionLimits, Grammar};
+use crate::Error;
+use std::collections::{HashMap, HashSet};
+
+/// Right hand side of a rule in a text or XML grammar.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expansion {
+    /// One or more words, separated by whitespace.
+    Token(String),
+
+    /// Expansions that are matched one after the other.
+    Sequence(Vec<Expansion>),
+
+    /// Expansions of which only one is matched.
+    Alternatives(Vec<Expansion>),
+
+    /// An expansion that is matched between `min` and `max` times, or
+    /// an unbounded amount of times if `max` is [`None`].
+    Repeat {
+        inner: Box<Expansion>,
+        min: usize,
+        max: Option<usize>,
+    },
+
+    /// Reference to another rule.
+    RuleRef(String),
+
+    /// Tag that is emitted when the expansion it is attached to is matched.
+    Tag(String),
+
+    /// Matches nothing, so it is always matched.
+    Null,
+
+    /// Can never be matched.
+    Void,
+}
+
+impl Expansion {
+    /// Returns an expansion that matches `inner` zero or one times.
+    pub(crate) fn optional(inner: Expansion) -> Self {
+        Self::Repeat {
+            inner: Box::new(inner),
+            min: 0,
+            max: Some(1),
+        }
+    }
+
+    /// Returns `expansions` as a single expansion, without wrapping it if there is only one.
+    pub(crate) fn sequence(mut expansions: Vec<Expansion>) -> Self {
+        if expansions.len() == 1 {
+            expansions.remove(0)
+        } else {
+            Self::Sequence(expansions)
+        }
+    }
+
+    /// Returns `expansions` as a single expansion, without wrapping it if there is only one.
+    pub(crate) fn alternatives(mut expansions: Vec<Expansion>) -> Self {
+        if expansions.len() == 1 {
+            expansions.remove(0)
+        } else {
+            Self::Alternatives(expansions)
+        }
+    }
+}
+
+/// Step in a path through a grammar.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Event {
+    Word(String),
+    Tag(String),
+    RuleStart(String),
+    RuleEnd,
+}
+
+/// A way of matching a rule, as the steps taken through the grammar.
+pub(crate) type Path = Vec<Event>;
+
+/// Returns the words in `path`, separated by spaces.
+pub(crate) fn path_text(path: &[Event]) -> String {
+    path.iter()
+        .filter_map(|event| match event {
+            Event::Word(word) => Some(word.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns `text` with its words separated by a single space, like the text of a path.
+pub(crate) fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns a [`Grammar`] with the non-empty `texts`, without duplicates.
+pub(crate) fn texts_grammar<'a>(texts: impl IntoIterator<Item = &'a str>) -> Grammar {
+    let mut seen = HashSet::new();
+    let mut unique: Vec<&str> = Vec::new();
+    for text in texts {
+        if !text.is_empty() && seen.insert(text) {
+            unique.push(text);
+        }
+    }
+
+    Grammar::from_phrases(unique)
+}
+
+/// Expands rules into every path that matches them.
+pub(crate) struct Expander<'a> {
+    rules: &'a HashMap<String, Expansion>,
+    limits: &'a ExpansionLimits,
+
+    /// Rules that are being expanded, used to detect recursion.
+    stack: Vec<&'a str>,
+}
+
+impl<'a> Expander<'a> {
+    pub(crate) fn new(rules: &'a HashMap<String, Expansion>, limits: &'a ExpansionLimits) -> Self {
+        Self {
+            rules,
+            limits,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Returns every path that matches the rule called `name`.
+    pub(crate) fn expand_rule(&mut self, name: &str) -> Result<Vec<Path>, Error> {
+        if self.stack.contains(&name) {
+            return Err(Error::GrammarExpansion(format!(
+                "rule {name:?} is recursive, which is not supported"
+            )));
+        }
+
+        let (key, expansion) = self
+            .rules
+            .get_key_value(name)
+            .ok_or_else(|| Error::GrammarExpansion(format!("rule {name:?} is not defined")))?;
+
+        self.stack.push(key);
+        let paths = self.expand(expansion);
+        self.stack.pop();
+
+        Ok(paths?
+            .into_iter()
+            .map(|path| {
+                let mut wrapped = Vec::with_capacity(path.len() + 2);
+                wrapped.push(Event::RuleStart(name.to_owned()));
+                wrapped.extend(path);
+                wrapped.push(Event::RuleEnd);
+                wrapped
+            })
+            .collect())
+    }
+
+    fn expand(&mut self, expansion: &'a Expansion) -> Result<Vec<Path>, Error> {
+        let paths = match expansion {
+            Expansion::Token(token) => vec![token
+                .split_whitespace()
+                .map(|word| Event::Word(word.to_owned()))
+                .collect()],
+            Expansion::Sequence(expansions) => {
+                let mut paths = vec![Vec::new()];
+                for expansion in expansions {
+                    let next = self.expand(expansion)?;
+                    paths = self.concat(&paths, &next)?;
+                }
+
+                paths
+            }
+            Expansion::Alternatives(expansions) => {
+                let mut paths = Vec::new();
+                for expansion in expansions {
+                    paths.extend(self.expand(expansion)?);
+                    self.check_len(paths.len())?;
+                }
+
+                paths
+            }
+            Expansion::Repeat { inner, min, max } => {
+                let inner = self.expand(inner)?;
+                let max = max.unwrap_or_else(|| self.limits.max_repeats.max(*min));
+
+                // Paths with exactly `count` repetitions
+                let mut repeated = vec![Vec::new()];
+                let mut paths = Vec::new();
+                for count in 0..=max {
+                    if count >= *min {
+                        paths.extend(repeated.iter().cloned());
+                        self.check_len(paths.len())?;
+                    }
+                    if count < max {
+                        repeated = self.concat(&repeated, &inner)?;
+                        // Nothing can match more repetitions of an inner expansion
+                        // without paths, like void, however large `max` is
+                        if repeated.is_empty() {
+                            break;
+                        }
+                    }
+                }
+
+                paths
+            }
+            Expansion::RuleRef(name) => self.expand_rule(name)?,
+            Expansion::Tag(tag) => vec![vec![Event::Tag(tag.clone())]],
+            Expansion::Null => vec![Vec::new()],
+            Expansion::Void => Vec::new(),
+        };
+
+        self.check_len(paths.len())?;
+        Ok(paths)
+    }
+
+    /// Returns every path in `first` followed by every path in `second`.
+    fn concat(&self, first: &[Path], second: &[Path]) -> Result<Vec<Path>, Error> {
+        self.check_len(first.len().saturating_mul(second.len()))?;
+
+        Ok(first
+            .iter()
+            .flat_map(|a| {
+                second
+                    .iter()
+                    .map(move |b| a.iter().chain(b).cloned().collect())
+            })
+            .collect())
+    }
+
+    fn check_len(&self, len: usize) -> Result<(), Error> {
+        if len > self.limits.max_phrases {
+            Err(Error::GrammarExpansion(format!(
+                "grammar expands to more than {} phrases",
+                self.limits.max_phrases
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str) -> Expansion {
+        Expansion::Token(text.to_owned())
+    }
+
+    fn repeat(inner: Expansion, min: usize, max: Option<usize>) -> Expansion {
+        Expansion::Repeat {
+            inner: Box::new(inner),
+            min,
+            max,
+        }
+    }
+
+    /// Expands the rule `main` of `rules`, returning the text of each path.
+    fn expand(
+        rules: impl IntoIterator<Item = (&'static str, Expansion)>,
+        limits: &ExpansionLimits,
+    ) -> Result<Vec<String>, Error> {
+        let rules: HashMap<_, _> = rules
+            .into_iter()
+            .map(|(name, expansion)| (name.to_owned(), expansion))
+            .collect();
+
+        Ok(Expander::new(&rules, limits)
+            .expand_rule("main")?
+            .iter()
+            .map(|path| path_text(path))
+            .collect())
+    }
+
+    fn expansion_error(result: Result<Vec<String>, Error>) -> String {
+        match result {
+            Err(Error::GrammarExpansion(message)) => message,
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn paths_are_wrapped_in_rule_events() {
+        let rules = HashMap::from([
+            (
+                "main".to_owned(),
+                Expansion::Sequence(vec![
+                    token("hi"),
+                    Expansion::RuleRef("name".to_owned()),
+                    Expansion::Tag("greeting".to_owned()),
+                ]),
+            ),
+            ("name".to_owned(), token("bob")),
+        ]);
+        let paths = Expander::new(&rules, &ExpansionLimits::default())
+            .expand_rule("main")
+            .unwrap();
+
+        assert_eq!(
+            paths,
+            [vec![
+                Event::RuleStart("main".to_owned()),
+                Event::Word("hi".to_owned()),
+                Event::RuleStart("name".to_owned()),
+                Event::Word("bob".to_owned()),
+                Event::RuleEnd,
+                Event::Tag("greeting".to_owned()),
+                Event::RuleEnd,
+            ]]
+        );
+    }
+
+    #[test]
+    fn repeats_are_bounded() {
+        let limits = ExpansionLimits {
+            max_repeats: 2,
+            ..ExpansionLimits::default()
+        };
+
+        let texts = expand([("main", repeat(token("a"), 1, Some(3)))], &limits);
+        assert_eq!(texts.unwrap(), ["a", "a a", "a a a"]);
+
+        // Unbounded repeats stop at `max_repeats`, unless they need more
+        let texts = expand([("main", repeat(token("a"), 0, None))], &limits);
+        assert_eq!(texts.unwrap(), ["", "a", "a a"]);
+        let texts = expand([("main", repeat(token("a"), 3, None))], &limits);
+        assert_eq!(texts.unwrap(), ["a a a"]);
+
+        let texts = expand([("main", repeat(token("a"), 2, Some(2)))], &limits);
+        assert_eq!(texts.unwrap(), ["a a"]);
+    }
+
+    #[test]
+    fn null_and_void() {
+        let limits = ExpansionLimits::default();
+
+        let texts = expand(
+            [(
+                "main",
+                Expansion::Sequence(vec![token("a"), Expansion::Null, token("b")]),
+            )],
+            &limits,
+        );
+        assert_eq!(texts.unwrap(), ["a b"]);
+
+        // Void removes the sequences it is in, but not the other alternatives
+        let texts = expand(
+            [(
+                "main",
+                Expansion::Alternatives(vec![
+                    Expansion::Sequence(vec![token("a"), Expansion::Void]),
+                    token("b"),
+                ]),
+            )],
+            &limits,
+        );
+        assert_eq!(texts.unwrap(), ["b"]);
+
+        // Repeating void can still match nothing
+        let texts = expand([("main", repeat(Expansion::Void, 0, Some(2)))], &limits);
+        assert_eq!(texts.unwrap(), [""]);
+    }
+
+    #[test]
+    fn repeating_void_stops_at_the_first_repetition() {
+        let limits = ExpansionLimits {
+            max_repeats: usize::MAX,
+            ..ExpansionLimits::default()
+        };
+        let void = Expansion::Alternatives(vec![Expansion::Void, Expansion::Void]);
+
+        // Would take billions of iterations without stopping early
+        let texts = expand(
+            [("main", repeat(void.clone(), 0, Some(usize::MAX)))],
+            &limits,
+        );
+        assert_eq!(texts.unwrap(), [""]);
+        let texts = expand([("main", repeat(void.clone(), 0, None))], &limits);
+        assert_eq!(texts.unwrap(), [""]);
+        let texts = expand([("main", repeat(void, 1, Some(usize::MAX)))], &limits);
+        assert!(texts.unwrap().is_empty());
+    }
+
+    #[test]
+    fn recursion_is_rejected() {
+        let rules = [
+            ("main", Expansion::RuleRef("loop".to_owned())),
+            (
+                "loop",
+                Expansion::Alternatives(vec![
+                    token("end"),
+                    Expansion::Sequence(vec![
+                        token("again"),
+                        Expansion::RuleRef("loop".to_owned()),
+                    ]),
+                ]),
+            ),
+        ];
+
+        assert_eq!(
+            expansion_error(expand(rules, &ExpansionLimits::default())),
+            r#"rule "loop" is recursive, which is not supported"#
+        );
+    }
+
+    #[test]
+    fn the_phrase_limit_is_checked_before_building_paths() {
+        let limits = ExpansionLimits {
+            max_repeats: usize::MAX,
+            max_phrases: 100,
+        };
+        let digit = Expansion::Alternatives((0..10).map(|i| token(&i.to_string())).collect());
+
+        let texts = expand([("main", repeat(digit.clone(), 2, Some(2)))], &limits);
+        assert_eq!(texts.unwrap().len(), 100);
+
+        // Would never finish if the paths were built before checking the limit
+        assert_eq!(
+            expansion_error(expand([("main", repeat(digit, 0, None))], &limits)),
+            "grammar expands to more than 100 phrases"
+        );
+    }
+
+    #[test]
+    fn texts_are_normalized_and_deduplicated() {
+        assert_eq!(
+            normalize_text("  turn \t on\nthe   light "),
+            "turn on the light"
+        );
+
+        let grammar = texts_grammar(["yes", "", "no", "yes"]);
+        assert_eq!(grammar.phrases(), ["yes", "no"]);
+    }
+}
@@ -0,0 +1,580 @@
+//! Support for grammars in the [Java Speech Grammar Format](https://www.w3.org/TR/jsgf/).
+//!
+//! Vosk only understands flat lists of phrases, so the rules of a [`JsgfGrammar`]
+//! are expanded into every phrase they can match. Tags are kept for each phrase,
+//! so a recognized text can be mapped back to them with [`JsgfPhrases::find`].
+//!
+//! Imports are not supported, and neither are recursive rules, as
+//! they cannot be expanded into a finite list of phrases.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use vosk::{grammar::{jsgf::JsgfGrammar, ExpansionLimits}, Model, Recognizer};
+//! #
+//! let jsgf: JsgfGrammar = "
+//!     #JSGF V1.0;
+//!     grammar lights;
+//!
+//!     public <command> = (turn | switch) (on {on} | off {off}) the <device>;
+//!     <device> = light {light} | fan {fan};
+//! "
+//! .parse()
+//! .expect("Invalid grammar");
+//!
+//! let phrases = jsgf.expand(&ExpansionLimits::default()).expect("Could not expand the grammar");
+//!
+//! let model = Model::new("/path/to/model").expect("Could not create a model");
+//! let mut recognizer =
+//!     Recognizer::new_with_grammar(&model, model.sample_rate(), &phrases.grammar().with_unk())
+//!         .expect("Could not create a recognizer");
+//!
+//! // Feed audio to the recognizer...
+//!
+//! let result = recognizer.final_result().single().unwrap();
+//! if let Some(phrase) = phrases.find(&result.text) {
+//!     // e.g. ["on", "light"]
+//!     println!("{:?}", phrase.tags);
+//! }
+//! ```
+
+use super::{
+    cursor::Cursor,
+    expansion::{normalize_text, path_text, texts_grammar, Event, Expander, Expansion},
+    ExpansionLimits, Grammar,
+};
+use crate::Error;
+use std::{collections::HashMap, str::FromStr};
+
+/// Characters that cannot be part of an unquoted token.
+const RESERVED: &[char] = &[
+    ';', '=', '|', '*', '+', '<', '>', '(', ')', '[', ']', '{', '}', '/', '"',
+];
+
+/// A parsed JSGF grammar.
+///
+/// Created with [`JsgfGrammar::parse`] or [`str::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsgfGrammar {
+    name: Option<String>,
+    rules: HashMap<String, Expansion>,
+    public_rules: Vec<String>,
+}
+
+impl JsgfGrammar {
+    /// Parses a JSGF grammar.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut cursor = Cursor::new(source);
+        let mut grammar = Self {
+            name: None,
+            rules: HashMap::new(),
+            public_rules: Vec::new(),
+        };
+
+        cursor.skip_trivia();
+        if cursor.rest().starts_with("#JSGF") {
+            cursor.take_until(';')?;
+            cursor.skip_trivia();
+        }
+
+        if cursor.eat_keyword("grammar") {
+            grammar.name = Some(cursor.take_until(';')?.trim().to_owned());
+        }
+
+        loop {
+            cursor.skip_trivia();
+            if cursor.is_eof() {
+                break;
+            }
+
+            if cursor.eat_keyword("import") {
+                return Err(cursor.error("imports are not supported"));
+            }
+
+            let public = cursor.eat_keyword("public");
+            cursor.skip_trivia();
+            cursor.expect('<')?;
+            let name = cursor.take_until('>')?.trim().to_owned();
+
+            cursor.skip_trivia();
+            cursor.expect('=')?;
+            let expansion = parse_alternatives(&mut cursor)?;
+            cursor.skip_trivia();
+            cursor.expect(';')?;
+
+            if grammar.rules.insert(name.clone(), expansion).is_some() {
+                return Err(cursor.error(format!("rule <{name}> is defined more than once")));
+            }
+            if public {
+                grammar.public_rules.push(name);
+            }
+        }
+
+        Ok(grammar)
+    }
+
+    /// Returns the name of the grammar, as declared with `grammar <name>;`.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the names of the public rules, in the order they were defined.
+    #[must_use]
+    pub fn public_rules(&self) -> &[String] {
+        &self.public_rules
+    }
+
+    /// Expands all the public rules into the phrases they can match.
+    pub fn expand(&self, limits: &ExpansionLimits) -> Result<JsgfPhrases, Error> {
+        if self.public_rules.is_empty() {
+            return Err(Error::GrammarExpansion(
+                "grammar does not have any public rules".to_owned(),
+            ));
+        }
+
+        let mut expander = Expander::new(&self.rules, limits);
+        let mut phrases = Vec::new();
+        for rule in &self.public_rules {
+            phrases.extend(expand_rule(&mut expander, rule)?);
+        }
+
+        Ok(JsgfPhrases { phrases })
+    }
+
+    /// Expands the rule called `rule`, which does not need to be public, into the phrases it can match.
+    pub fn expand_rule(&self, rule: &str, limits: &ExpansionLimits) -> Result<JsgfPhrases, Error> {
+        let phrases = expand_rule(&mut Expander::new(&self.rules, limits), rule)?;
+        Ok(JsgfPhrases { phrases })
+    }
+}
+
+impl FromStr for JsgfGrammar {
+    type Err = Error;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+/// Phrases that a [`JsgfGrammar`] can match, returned by [`JsgfGrammar::expand`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsgfPhrases {
+    phrases: Vec<JsgfPhrase>,
+}
+
+impl JsgfPhrases {
+    /// Returns all the phrases, in the order they were expanded.
+    #[must_use]
+    pub fn phrases(&self) -> &[JsgfPhrase] {
+        &self.phrases
+    }
+
+    /// Returns the [`Grammar`] to pass to [`Recognizer::new_with_grammar`], without duplicates.
+    ///
+    /// [`Recognizer::new_with_grammar`]: crate::Recognizer::new_with_grammar
+    #[must_use]
+    pub fn grammar(&self) -> Grammar {
+        texts_grammar(self.phrases.iter().map(|phrase| phrase.text.as_str()))
+    }
+
+    /// Returns the first phrase whose text is `text`, ignoring differences in whitespace.
+    ///
+    /// Useful to find the tags that were matched by a recognized text.
+    #[must_use]
+    pub fn find(&self, text: &str) -> Option<&JsgfPhrase> {
+        let text = normalize_text(text);
+        self.phrases.iter().find(|phrase| phrase.text == text)
+    }
+}
+
+/// A phrase that a [`JsgfGrammar`] can match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsgfPhrase {
+    /// Text of the phrase, with words separated by a single space.
+    pub text: String,
+
+    /// Name of the rule that was expanded into this phrase.
+    pub rule: String,
+
+    /// Contents of the tags that were matched, in order, without the surrounding braces.
+    pub tags: Vec<String>,
+}
+
+/// Expands `rule` into [`JsgfPhrase`]s.
+fn expand_rule(expander: &mut Expander, rule: &str) -> Result<Vec<JsgfPhrase>, Error> {
+    Ok(expander
+        .expand_rule(rule)?
+        .into_iter()
+        .map(|path| JsgfPhrase {
+            text: path_text(&path),
+            rule: rule.to_owned(),
+            tags: path
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::Tag(tag) => Some(tag),
+                    _ => None,
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Parses alternatives separated by `|`, each of them with an optional `/weight/`.
+fn parse_alternatives(cursor: &mut Cursor) -> Result<Expansion, Error> {
+    let mut alternatives = Vec::new();
+    loop {
+        cursor.skip_trivia();
+        if cursor.eat('/') {
+            // Weights only matter for the probability of each alternative, which
+            // Vosk does not support, so they are ignored
+            cursor.take_until('/')?;
+        }
+
+        alternatives.push(parse_sequence(cursor)?);
+        cursor.skip_trivia();
+        if !cursor.eat('|') {
+            break;
+        }
+    }
+
+    Ok(Expansion::alternatives(alternatives))
+}
+
+/// Parses expansions until the end of the sequence.
+fn parse_sequence(cursor: &mut Cursor) -> Result<Expansion, Error> {
+    let mut sequence = Vec::new();
+    loop {
+        cursor.skip_trivia();
+        match cursor.peek() {
+            None | Some(';' | '|' | ')' | ']') => break,
+            _ => sequence.push(parse_unary(cursor)?),
+        }
+    }
+
+    if sequence.is_empty() {
+        return Err(cursor.unexpected("an expansion"));
+    }
+
+    Ok(Expansion::sequence(sequence))
+}
+
+/// Parses an expansion followed by any amount of `*`, `+` and tags.
+fn parse_unary(cursor: &mut Cursor) -> Result<Expansion, Error> {
+    let mut expansion = parse_primary(cursor)?;
+
+    loop {
+        cursor.skip_trivia();
+        let min = match cursor.peek() {
+            Some('*') => 0,
+            Some('+') => 1,
+            Some('{') => {
+                cursor.bump();
+                let tag = parse_tag(cursor)?;
+                expansion = Expansion::Sequence(vec![expansion, Expansion::Tag(tag)]);
+                continue;
+            }
+            _ => break,
+        };
+
+        cursor.bump();
+        expansion = Expansion::Repeat {
+            inner: Box::new(expansion),
+            min,
+            max: None,
+        };
+    }
+
+    Ok(expansion)
+}
+
+/// Parses a token, rule reference, group or optional group.
+fn parse_primary(cursor: &mut Cursor) -> Result<Expansion, Error> {
+    match cursor.peek() {
+        Some('<') => {
+            cursor.bump();
+            let name = cursor.take_until('>')?.trim();
+
+            Ok(match name {
+                "NULL" => Expansion::Null,
+                "VOID" => Expansion::Void,
+                _ => Expansion::RuleRef(name.to_owned()),
+            })
+        }
+        Some('(') => {
+            cursor.bump();
+            let expansion = parse_alternatives(cursor)?;
+            cursor.skip_trivia();
+            cursor.expect(')')?;
+
+            Ok(expansion)
+        }
+        Some('[') => {
+            cursor.bump();
+            let expansion = parse_alternatives(cursor)?;
+            cursor.skip_trivia();
+            cursor.expect(']')?;
+
+            Ok(Expansion::optional(expansion))
+        }
+        Some('"') => {
+            cursor.bump();
+            let mut token = String::new();
+            loop {
+                match cursor.bump() {
+                    Some('"') => break,
+                    Some('\\') => token.extend(cursor.bump()),
+                    Some(c) => token.push(c),
+                    None => return Err(cursor.unexpected("'\"'")),
+                }
+            }
+
+            Ok(Expansion::Token(token))
+        }
+        Some(c) if !c.is_whitespace() && !RESERVED.contains(&c) => {
+            let token = cursor.take_while(|c| !c.is_whitespace() && !RESERVED.contains(&c));
+            Ok(Expansion::Token(token.to_owned()))
+        }
+        _ => Err(cursor.unexpected("an expansion")),
+    }
+}
+
+/// Parses the contents of a tag, after the opening `{`.
+fn parse_tag(cursor: &mut Cursor) -> Result<String, Error> {
+    let mut tag = String::new();
+    loop {
+        match cursor.bump() {
+            Some('}') => break,
+            Some('\\') => tag.extend(cursor.bump()),
+            Some(c) => tag.push(c),
+            None => return Err(cursor.unexpected("'}'")),
+        }
+    }
+
+    Ok(tag.trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(source: &str, limits: &ExpansionLimits) -> Result<Vec<String>, Error> {
+        let grammar = JsgfGrammar::parse(source).unwrap();
+        Ok(grammar
+            .expand(limits)?
+            .phrases()
+            .iter()
+            .map(|phrase| phrase.text.clone())
+            .collect())
+    }
+
+    fn expansion_error(source: &str, limits: &ExpansionLimits) -> String {
+        match texts(source, limits) {
+            Err(Error::GrammarExpansion(message)) => message,
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    fn parse_error(source: &str) -> (usize, usize, String) {
+        match JsgfGrammar::parse(source) {
+            Err(Error::GrammarParse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn header_and_rules_are_parsed() {
+        let grammar = JsgfGrammar::parse(
+            "#JSGF V1.0 UTF-8 en;
+            // Comments are ignored
+            grammar com.example.lights;
+
+            /* Private rules are only expanded when referenced */
+            <device> = light | fan;
+            public <command> = (turn | switch) on the <device>;
+            public <stop> = stop;",
+        )
+        .unwrap();
+
+        assert_eq!(grammar.name(), Some("com.example.lights"));
+        assert_eq!(grammar.public_rules(), ["command", "stop"]);
+
+        let phrases = grammar.expand(&ExpansionLimits::default()).unwrap();
+        let texts: Vec<_> = phrases
+            .phrases()
+            .iter()
+            .map(|phrase| (phrase.text.as_str(), phrase.rule.as_str()))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                ("turn on the light", "command"),
+                ("turn on the fan", "command"),
+                ("switch on the light", "command"),
+                ("switch on the fan", "command"),
+                ("stop", "stop")
+            ]
+        );
+
+        let device = grammar
+            .expand_rule("device", &ExpansionLimits::default())
+            .unwrap();
+        assert_eq!(device.phrases().len(), 2);
+    }
+
+    #[test]
+    fn weights_are_ignored() {
+        let texts = texts(
+            "public <answer> = /10/ yes | /0.5/ no | maybe;",
+            &ExpansionLimits::default(),
+        );
+        assert_eq!(texts.unwrap(), ["yes", "no", "maybe"]);
+    }
+
+    #[test]
+    fn optional_groups_and_repeats() {
+        let limits = ExpansionLimits {
+            max_repeats: 2,
+            ..ExpansionLimits::default()
+        };
+
+        assert_eq!(
+            texts("public <a> = [please] go;", &limits).unwrap(),
+            ["go", "please go"]
+        );
+        assert_eq!(
+            texts("public <a> = go+;", &limits).unwrap(),
+            ["go", "go go"]
+        );
+        assert_eq!(
+            texts("public <a> = (go | stop)* now;", &limits).unwrap(),
+            [
+                "now",
+                "go now",
+                "stop now",
+                "go go now",
+                "go stop now",
+                "stop go now",
+                "stop stop now"
+            ]
+        );
+    }
+
+    #[test]
+    fn null_and_void_rules() {
+        let limits = ExpansionLimits::default();
+
+        assert_eq!(
+            texts("public <a> = hello <NULL> world;", &limits).unwrap(),
+            ["hello world"]
+        );
+        assert_eq!(
+            texts("public <a> = hello | <VOID> world | bye;", &limits).unwrap(),
+            ["hello", "bye"]
+        );
+        assert!(texts("public <a> = <VOID>;", &limits).unwrap().is_empty());
+    }
+
+    #[test]
+    fn quoted_tokens_are_single_tokens() {
+        let grammar = JsgfGrammar::parse(
+            r#"public <city> = "new york" {ny} | "say \"hi\" \\ now" | los angeles;"#,
+        )
+        .unwrap();
+        let phrases = grammar.expand(&ExpansionLimits::default()).unwrap();
+
+        let texts: Vec<_> = phrases
+            .phrases()
+            .iter()
+            .map(|phrase| &phrase.text)
+            .collect();
+        assert_eq!(texts, ["new york", r#"say "hi" \ now"#, "los angeles"]);
+    }
+
+    #[test]
+    fn tags_are_kept_in_order() {
+        let grammar = JsgfGrammar::parse(
+            r"public <command> = (turn | switch) (on {on} | off { off }) the <device> {\}done};
+            <device> = light {light} | fan {fan};",
+        )
+        .unwrap();
+        let phrases = grammar.expand(&ExpansionLimits::default()).unwrap();
+
+        let phrase = phrases.find("switch  off the fan").unwrap();
+        assert_eq!(phrase.tags, ["off", "fan", "}done"]);
+        assert_eq!(phrase.rule, "command");
+        assert!(phrases.find("switch off the tv").is_none());
+
+        // Duplicates are removed from the grammar passed to Vosk
+        assert_eq!(phrases.grammar().phrases().len(), 8);
+    }
+
+    #[test]
+    fn recursive_and_undefined_rules_are_rejected() {
+        let limits = ExpansionLimits::default();
+
+        let message = expansion_error("public <a> = x <b>; <b> = y [<a>];", &limits);
+        assert!(message.contains("recursive"), "{message}");
+
+        let message = expansion_error("public <a> = x <b>;", &limits);
+        assert_eq!(message, r#"rule "b" is not defined"#);
+
+        let message = expansion_error("<a> = x;", &limits);
+        assert!(message.contains("public"), "{message}");
+
+        // A rule can be referenced more than once if it is not recursive
+        assert_eq!(
+            texts("public <a> = <b> <b>; <b> = x;", &limits).unwrap(),
+            ["x x"]
+        );
+    }
+
+    #[test]
+    fn expansion_limits_are_enforced() {
+        let source = "public <a> = <digit> <digit> <digit>;
+            <digit> = 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9;";
+
+        let limits = ExpansionLimits {
+            max_phrases: 999,
+            ..ExpansionLimits::default()
+        };
+        let message = expansion_error(source, &limits);
+        assert_eq!(message, "grammar expands to more than 999 phrases");
+
+        let limits = ExpansionLimits {
+            max_phrases: 1000,
+            ..ExpansionLimits::default()
+        };
+        assert_eq!(texts(source, &limits).unwrap().len(), 1000);
+    }
+
+    #[test]
+    fn invalid_grammars_are_rejected() {
+        let (line, column, message) = parse_error("public <a> = yes\n| no\n<b> = maybe;");
+        // The missing ';' is only noticed once the next rule is read as part of this one
+        assert_eq!((line, column), (3, 5));
+        assert_eq!(message, "expected an expansion, found '='");
+
+        let (_, _, message) = parse_error("import <com.example.*>;");
+        assert_eq!(message, "imports are not supported");
+
+        let (_, _, message) = parse_error("<a> = x; <a> = y;");
+        assert_eq!(message, "rule <a> is defined more than once");
+
+        let (_, _, message) = parse_error("public <a> = x {tag;");
+        assert_eq!(message, "expected '}', found the end of the grammar");
+
+        let (_, _, message) = parse_error(r#"public <a> = "x;"#);
+        assert_eq!(message, r#"expected '"', found the end of the grammar"#);
+
+        let (_, _, message) = parse_error("public <a> = (x | y;");
+        assert_eq!(message, "expected ')', found ';'");
+
+        let (_, _, message) = parse_error("public <a> = ;");
+        assert_eq!(message, "expected an expansion, found ';'");
+    }
+}
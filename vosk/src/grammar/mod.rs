@@ -0,0 +1,196 @@
+//! Grammars that restrict the phrases a [`Recognizer`] can recognize.
+//!
+//! [`Recognizer`]: crate::Recognizer
+
+mod cursor;
+mod expansion;
+pub mod jsgf;
+pub mod srgs;
+
+use crate::{Error, Model};
+
+/// Special token that matches any word that is not part of the grammar.
+pub const UNK: &str = "[unk]";
+
+/// Limits used when expanding a text grammar, like a [JSGF](jsgf) one, into a list of phrases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpansionLimits {
+    /// Maximum amount of repetitions of an expansion with an unbounded repeat (`*` or `+`).
+    pub max_repeats: usize,
+
+    /// Maximum amount of phrases a grammar can expand to. Expanding a grammar
+    /// that exceeds it fails with [`Error::GrammarExpansion`].
+    pub max_phrases: usize,
+}
+
+impl Default for ExpansionLimits {
+    fn default() -> Self {
+        Self {
+            max_repeats: 3,
+            max_phrases: 10_000,
+        }
+    }
+}
+
+/// List of phrases to recognize, passed to [`Recognizer::new_with_grammar`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use vosk::{grammar::Grammar, Model, Recognizer};
+/// #
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let grammar = Grammar::from_phrases(["turn on the light", "turn off the light"]).with_unk();
+///
+/// grammar.validate(&model).expect("The grammar contains unknown words");
+/// let recognizer = Recognizer::new_with_grammar(&model, model.sample_rate(), &grammar)
+///     .expect("Could not create a recognizer");
+/// ```
+///
+/// [`Recognizer::new_with_grammar`]: crate::Recognizer::new_with_grammar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grammar {
+    phrases: Vec<String>,
+    unk: bool,
+}
+
+impl Grammar {
+    /// Creates an empty grammar.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a grammar with the given phrases.
+    ///
+    /// A phrase equal to [`UNK`] enables it, like [`with_unk`] does.
+    ///
+    /// [`with_unk`]: Self::with_unk
+    #[must_use]
+    pub fn from_phrases<S: Into<String>>(phrases: impl IntoIterator<Item = S>) -> Self {
+        let mut grammar = Self::new();
+        for phrase in phrases {
+            grammar.add_phrase(phrase);
+        }
+
+        grammar
+    }
+
+    /// Adds a phrase to the grammar.
+    ///
+    /// A phrase equal to [`UNK`] enables it, like [`set_unk`] does.
+    ///
+    /// [`set_unk`]: Self::set_unk
+    pub fn add_phrase(&mut self, phrase: impl Into<String>) {
+        let phrase = phrase.into();
+        if phrase.trim() == UNK {
+            self.unk = true;
+        } else {
+            self.phrases.push(phrase);
+        }
+    }
+
+    /// Adds a phrase to the grammar and returns it. See [`add_phrase`].
+    ///
+    /// [`add_phrase`]: Self::add_phrase
+    #[must_use]
+    pub fn with_phrase(mut self, phrase: impl Into<String>) -> Self {
+        self.add_phrase(phrase);
+        self
+    }
+
+    /// Enables or disables the [`UNK`] token, which will be the result for any word that
+    /// could not be recognized. Otherwise, the best match is returned, even if it is most
+    /// likely incorrect.
+    pub fn set_unk(&mut self, enable: bool) {
+        self.unk = enable;
+    }
+
+    /// Enables the [`UNK`] token and returns the grammar. See [`set_unk`].
+    ///
+    /// [`set_unk`]: Self::set_unk
+    #[must_use]
+    pub fn with_unk(mut self) -> Self {
+        self.set_unk(true);
+        self
+    }
+
+    /// Returns the phrases in the grammar, without the [`UNK`] token.
+    #[must_use]
+    pub fn phrases(&self) -> &[String] {
+        &self.phrases
+    }
+
+    /// Returns whether the [`UNK`] token is enabled.
+    #[must_use]
+    pub fn has_unk(&self) -> bool {
+        self.unk
+    }
+
+    /// Returns the grammar as the JSON array of strings Vosk expects.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let unk = self.unk.then_some(UNK);
+        let phrases: Vec<&str> = self.phrases.iter().map(String::as_str).chain(unk).collect();
+
+        serde_json::to_string(&phrases).expect("a list of strings is always valid JSON")
+    }
+
+    /// Returns the phrases with words that `model` cannot recognize.
+    ///
+    /// Vosk ignores these words when the grammar is used.
+    #[must_use]
+    pub fn out_of_vocabulary(&self, model: &Model) -> Vec<OutOfVocabulary> {
+        self.phrases
+            .iter()
+            .filter_map(|phrase| {
+                let words: Vec<String> = phrase
+                    .split_whitespace()
+                    .filter(|word| *word != UNK && model.find_word(word).is_none())
+                    .map(str::to_owned)
+                    .collect();
+
+                (!words.is_empty()).then(|| OutOfVocabulary {
+                    phrase: phrase.clone(),
+                    words,
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that all the words in the grammar can be recognized by `model`.
+    ///
+    /// Returns [`Error::OutOfVocabulary`] with the offending phrases otherwise.
+    pub fn validate(&self, model: &Model) -> Result<(), Error> {
+        let out_of_vocabulary = self.out_of_vocabulary(model);
+        if out_of_vocabulary.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::OutOfVocabulary(out_of_vocabulary))
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Grammar {
+    fn from_iter<T: IntoIterator<Item = S>>(phrases: T) -> Self {
+        Self::from_phrases(phrases)
+    }
+}
+
+impl<S: Into<String>> Extend<S> for Grammar {
+    fn extend<T: IntoIterator<Item = S>>(&mut self, phrases: T) {
+        for phrase in phrases {
+            self.add_phrase(phrase);
+        }
+    }
+}
+
+/// A phrase in a [`Grammar`] with words that a [`Model`] cannot recognize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfVocabulary {
+    /// The phrase, as it was added to the grammar.
+    pub phrase: String,
+
+    /// The words in the phrase that are not in the model's vocabulary.
+    pub words: Vec<String>,
+}
@@ -0,0 +1,368 @@
+use super::{
+    parse_repeat, repeat,
+    script::{Script, TagFormat},
+    SrgsGrammar,
+};
+use crate::{
+    grammar::{cursor::Cursor, expansion::Expansion, UNK},
+    Error,
+};
+use std::collections::HashMap;
+
+/// Characters that cannot be part of an unquoted token or a rule name.
+const RESERVED: &[char] = &[
+    ';', '=', '|', '*', '+', '<', '>', '(', ')', '[', ']', '{', '}', '/', '"', '$', '!',
+];
+
+/// Parses an SRGS grammar in the ABNF form.
+pub(super) fn parse(source: &str) -> Result<SrgsGrammar, Error> {
+    let mut cursor = Cursor::new(source);
+    let mut grammar = SrgsGrammar {
+        root: None,
+        rules: HashMap::new(),
+        tag_format: TagFormat::Script,
+    };
+
+    cursor.skip_trivia();
+    if cursor.rest().starts_with("#ABNF") {
+        cursor.take_until(';')?;
+    }
+
+    loop {
+        cursor.skip_trivia();
+        if cursor.is_eof() {
+            break;
+        }
+
+        if cursor.eat_keyword("mode") {
+            if cursor.take_until(';')?.trim() != "voice" {
+                return Err(cursor.error("only voice grammars are supported"));
+            }
+        } else if cursor.eat_keyword("root") {
+            cursor.skip_trivia();
+            cursor.expect('$')?;
+            grammar.root = Some(parse_name(&mut cursor)?.to_owned());
+            cursor.skip_trivia();
+            cursor.expect(';')?;
+        } else if cursor.eat_keyword("tag-format") {
+            cursor.skip_trivia();
+            cursor.expect('<')?;
+            grammar.tag_format =
+                TagFormat::from_name(cursor.take_until('>')?).map_err(|err| cursor.error(err))?;
+            cursor.skip_trivia();
+            cursor.expect(';')?;
+        } else if ["language", "base", "lexicon", "meta", "http-equiv"]
+            .into_iter()
+            .any(|keyword| cursor.eat_keyword(keyword))
+        {
+            cursor.take_until(';')?;
+        } else if cursor.eat('{') {
+            // Global tags are only used to initialize variables, which are not supported
+            parse_tag(&mut cursor, grammar.tag_format)?;
+            cursor.skip_trivia();
+            cursor.expect(';')?;
+        } else {
+            if !cursor.eat_keyword("public") {
+                cursor.eat_keyword("private");
+            }
+            cursor.skip_trivia();
+            cursor.expect('$')?;
+            let name = parse_name(&mut cursor)?;
+
+            cursor.skip_trivia();
+            cursor.expect('=')?;
+            let expansion = parse_alternatives(&mut cursor, grammar.tag_format)?;
+            cursor.skip_trivia();
+            cursor.expect(';')?;
+
+            grammar
+                .add_rule(name, expansion)
+                .map_err(|message| cursor.error(message))?;
+        }
+    }
+
+    Ok(grammar)
+}
+
+fn parse_name<'a>(cursor: &mut Cursor<'a>) -> Result<&'a str, Error> {
+    let name = cursor.take_while(|c| !c.is_whitespace() && !RESERVED.contains(&c));
+    if name.is_empty() {
+        Err(cursor.unexpected("a rule name"))
+    } else {
+        Ok(name)
+    }
+}
+
+/// Parses alternatives separated by `|`, each of them with an optional `/weight/`.
+fn parse_alternatives(cursor: &mut Cursor, format: TagFormat) -> Result<Expansion, Error> {
+    let mut alternatives = Vec::new();
+    loop {
+        cursor.skip_trivia();
+        if cursor.eat('/') {
+            // Weights only matter for the probability of each alternative, which
+            // Vosk does not support, so they are ignored
+            cursor.take_until('/')?;
+        }
+
+        alternatives.push(parse_sequence(cursor, format)?);
+        cursor.skip_trivia();
+        if !cursor.eat('|') {
+            break;
+        }
+    }
+
+    Ok(Expansion::alternatives(alternatives))
+}
+
+/// Parses expansions until the end of the sequence.
+fn parse_sequence(cursor: &mut Cursor, format: TagFormat) -> Result<Expansion, Error> {
+    let mut sequence = Vec::new();
+    loop {
+        cursor.skip_trivia();
+        match cursor.peek() {
+            None | Some(';' | '|' | ')' | ']') => break,
+            _ => sequence.push(parse_unary(cursor, format)?),
+        }
+    }
+
+    if sequence.is_empty() {
+        return Err(cursor.unexpected("an expansion"));
+    }
+
+    Ok(Expansion::sequence(sequence))
+}
+
+/// Parses an expansion followed by any amount of `<m-n>` repeats and `!lang` attachments.
+fn parse_unary(cursor: &mut Cursor, format: TagFormat) -> Result<Expansion, Error> {
+    let mut expansion = parse_primary(cursor, format)?;
+
+    loop {
+        cursor.skip_trivia();
+        if cursor.eat('<') {
+            let (min, max) =
+                parse_repeat(cursor.take_until('>')?).map_err(|err| cursor.error(err))?;
+            expansion = repeat(expansion, min, max);
+
+            // Repeat probabilities are ignored like weights
+            cursor.skip_trivia();
+            if cursor.eat('/') {
+                cursor.take_until('/')?;
+            }
+        } else if cursor.eat('!') {
+            // Vosk models only have one language, so language attachments are ignored
+            cursor.take_while(|c| !c.is_whitespace() && !RESERVED.contains(&c));
+        } else {
+            return Ok(expansion);
+        }
+    }
+}
+
+/// Parses a token, rule reference, tag, group or optional group.
+fn parse_primary(cursor: &mut Cursor, format: TagFormat) -> Result<Expansion, Error> {
+    match cursor.peek() {
+        Some('$') => {
+            cursor.bump();
+            if cursor.eat('<') {
+                let uri = cursor.take_until('>')?;
+                return match uri.strip_prefix('#') {
+                    Some(name) => Ok(Expansion::RuleRef(name.to_owned())),
+                    None => Err(cursor.error("external rule references are not supported")),
+                };
+            }
+
+            Ok(match parse_name(cursor)? {
+                "NULL" => Expansion::Null,
+                "VOID" => Expansion::Void,
+                "GARBAGE" => Expansion::Token(UNK.to_owned()),
+                name => Expansion::RuleRef(name.to_owned()),
+            })
+        }
+        Some('(') => {
+            cursor.bump();
+            let expansion = parse_alternatives(cursor, format)?;
+            cursor.skip_trivia();
+            cursor.expect(')')?;
+
+            Ok(expansion)
+        }
+        Some('[') => {
+            cursor.bump();
+            let expansion = parse_alternatives(cursor, format)?;
+            cursor.skip_trivia();
+            cursor.expect(']')?;
+
+            Ok(Expansion::optional(expansion))
+        }
+        Some('{') => {
+            cursor.bump();
+            Ok(Expansion::Tag(parse_tag(cursor, format)?))
+        }
+        Some('"') => {
+            cursor.bump();
+            Ok(Expansion::Token(cursor.take_until('"')?.to_owned()))
+        }
+        Some(c) if !c.is_whitespace() && !RESERVED.contains(&c) => {
+            let token = cursor.take_while(|c| !c.is_whitespace() && !RESERVED.contains(&c));
+            Ok(Expansion::Token(token.to_owned()))
+        }
+        _ => Err(cursor.unexpected("an expansion")),
+    }
+}
+
+/// Parses and checks the contents of a tag, after the opening `{`.
+///
+/// Tags whose contents contain `}` are written as `{!{ ... }!}`.
+fn parse_tag(cursor: &mut Cursor, format: TagFormat) -> Result<String, Error> {
+    let (content, (line, column)) = if cursor.rest().starts_with("!{") {
+        cursor.bump();
+        cursor.bump();
+        let position = cursor.position();
+        (cursor.take_until_str("}!}")?, position)
+    } else {
+        let position = cursor.position();
+        (cursor.take_until('}')?, position)
+    };
+
+    Script::parse(&mut Cursor::at(content, line, column), format)?;
+    Ok(content.trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        grammar::{srgs::SrgsGrammar, ExpansionLimits},
+        Error,
+    };
+
+    fn texts(source: &str) -> Vec<String> {
+        let grammar = SrgsGrammar::from_abnf(source).unwrap();
+        let mut texts: Vec<_> = grammar
+            .expand(&ExpansionLimits::default())
+            .unwrap()
+            .phrases()
+            .iter()
+            .map(|phrase| phrase.text.clone())
+            .collect();
+        texts.sort();
+        texts
+    }
+
+    #[test]
+    fn header_and_declarations_are_parsed() {
+        let grammar = SrgsGrammar::from_abnf(
+            "#ABNF 1.0 UTF-8;\nlanguage en-US;\nmode voice;\nroot $main;\n\
+             tag-format <semantics/1.0>;\nmeta \"author\" is \"me\";\n\
+             { out = 1; };\npublic $main = hello;",
+        )
+        .unwrap();
+
+        assert_eq!(grammar.root(), Some("main"));
+    }
+
+    #[test]
+    fn alternatives_repeats_and_optional_groups() {
+        let texts = texts(
+            "root $main;
+            $main = /2/ (go | walk) [to the] $place<1-2> | /0.5/ stop;
+            $place = home!en-US | \"work place\";",
+        );
+
+        assert_eq!(
+            texts,
+            [
+                "go home",
+                "go home home",
+                "go home work place",
+                "go to the home",
+                "go to the home home",
+                "go to the home work place",
+                "go to the work place",
+                "go to the work place home",
+                "go to the work place work place",
+                "go work place",
+                "go work place home",
+                "go work place work place",
+                "stop",
+                "walk home",
+                "walk home home",
+                "walk home work place",
+                "walk to the home",
+                "walk to the home home",
+                "walk to the home work place",
+                "walk to the work place",
+                "walk to the work place home",
+                "walk to the work place work place",
+                "walk work place",
+                "walk work place home",
+                "walk work place work place",
+            ]
+        );
+    }
+
+    #[test]
+    fn special_rules_and_uri_references() {
+        let texts = texts(
+            "root $main;
+            $main = $<#number> $NULL (now | $VOID never | $GARBAGE);
+            $number = one | two;",
+        );
+
+        assert_eq!(texts, ["one [unk]", "one now", "two [unk]", "two now"]);
+    }
+
+    #[test]
+    fn tags_can_contain_braces() {
+        let grammar =
+            SrgsGrammar::from_abnf("root $a; $a = yes {!{ out = {}; out.ok = true; }!};").unwrap();
+        let phrases = grammar.expand(&ExpansionLimits::default()).unwrap();
+
+        assert_eq!(
+            phrases.interpret("yes"),
+            Some(&serde_json::json!({"ok": true}))
+        );
+    }
+
+    #[test]
+    fn invalid_grammars_are_rejected() {
+        let error = |source: &str| match SrgsGrammar::from_abnf(source) {
+            Err(Error::GrammarParse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            other => panic!("unexpected result {other:?}"),
+        };
+
+        let (_, _, message) = error("$a = a;\n$a = b;");
+        assert!(message.contains("more than once"), "{message}");
+
+        let (line, column, _) = error("root $a;\n$a = a b\n$b = c;");
+        assert_eq!((line, column), (3, 4));
+
+        let (_, _, message) = error("mode dtmf;");
+        assert!(message.contains("voice"), "{message}");
+
+        let (_, _, message) = error("$a = $<other.gram#b>;");
+        assert!(message.contains("external"), "{message}");
+
+        let (line, column, _) = error("$a = a {\n  out = window;\n};");
+        assert_eq!((line, column), (2, 15));
+
+        error("$a = (a | b;");
+        error("$a = a<2-1>;");
+        error("$a = ;");
+    }
+
+    #[test]
+    fn expansion_needs_a_root_rule() {
+        let grammar = SrgsGrammar::from_abnf("$a = a;").unwrap();
+
+        assert!(matches!(
+            grammar.expand(&ExpansionLimits::default()),
+            Err(Error::GrammarExpansion(_))
+        ));
+        assert!(grammar
+            .expand_rule("a", &ExpansionLimits::default())
+            .is_ok());
+    }
+}
@@ -0,0 +1,207 @@
+//! Support for [SRGS](https://www.w3.org/TR/speech-grammar/) grammars, in both
+//! their XML and ABNF forms, with [SISR](https://www.w3.org/TR/semantic-interpretation/)
+//! semantic interpretation.
+//!
+//! Vosk only understands flat lists of phrases, so the root rule of an [`SrgsGrammar`]
+//! is expanded into every phrase it can match, and the semantic interpretation of each
+//! phrase is computed from the tags along the way. After recognition, the interpretation
+//! of the recognized text can be looked up with [`SrgsPhrases::interpret`].
+//!
+//! Tags can use the `semantics/1.0-literals` format, or a subset of the `semantics/1.0`
+//! format (the default): assignments to `out` and its properties, like `out.size = "large";`,
+//! whose values can be literals, `out`, `rules.<name>`, `rules.latest()`, `meta.current().text`
+//! or `meta.<name>.text`, and `+` to add or concatenate them. Any other script is rejected
+//! when parsing the grammar.
+//!
+//! External rule references and recursive rules are not supported, and `GARBAGE` is
+//! matched by [`UNK`](super::UNK).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use vosk::{grammar::{srgs::SrgsGrammar, ExpansionLimits}, Model, Recognizer};
+//! #
+//! let srgs = SrgsGrammar::from_abnf(
+//!     r#"
+//!     #ABNF 1.0 UTF-8;
+//!     root $order;
+//!
+//!     $order = i want a $size coffee {out.size = rules.size;};
+//!     $size = small {out = "S";} | medium {out = "M";} | large {out = "L";};
+//!     "#,
+//! )
+//! .expect("Invalid grammar");
+//!
+//! let phrases = srgs.expand(&ExpansionLimits::default()).expect("Could not expand the grammar");
+//!
+//! let model = Model::new("/path/to/model").expect("Could not create a model");
+//! let mut recognizer =
+//!     Recognizer::new_with_grammar(&model, model.sample_rate(), &phrases.grammar().with_unk())
+//!         .expect("Could not create a recognizer");
+//!
+//! // Feed audio to the recognizer...
+//!
+//! let result = recognizer.final_result().single().unwrap();
+//! if let Some(interpretation) = phrases.interpret(&result.text) {
+//!     // e.g. {"size": "M"}
+//!     println!("{interpretation}");
+//! }
+//! ```
+
+mod abnf;
+mod script;
+mod xml;
+
+use self::script::{interpret, TagFormat};
+use super::{
+    expansion::{normalize_text, path_text, texts_grammar, Expander, Expansion},
+    ExpansionLimits, Grammar,
+};
+use crate::Error;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A parsed SRGS grammar.
+///
+/// Created with [`SrgsGrammar::from_xml`] or [`SrgsGrammar::from_abnf`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrgsGrammar {
+    root: Option<String>,
+    rules: HashMap<String, Expansion>,
+    tag_format: TagFormat,
+}
+
+impl SrgsGrammar {
+    /// Parses a grammar in the XML form.
+    pub fn from_xml(source: &str) -> Result<Self, Error> {
+        xml::parse(source)
+    }
+
+    /// Parses a grammar in the ABNF form.
+    pub fn from_abnf(source: &str) -> Result<Self, Error> {
+        abnf::parse(source)
+    }
+
+    /// Returns the name of the root rule, if the grammar declares one.
+    #[must_use]
+    pub fn root(&self) -> Option<&str> {
+        self.root.as_deref()
+    }
+
+    /// Expands the root rule into the phrases it can match.
+    pub fn expand(&self, limits: &ExpansionLimits) -> Result<SrgsPhrases, Error> {
+        let root = self.root.as_deref().ok_or_else(|| {
+            Error::GrammarExpansion("grammar does not declare a root rule".to_owned())
+        })?;
+
+        self.expand_rule(root, limits)
+    }
+
+    /// Expands the rule called `rule` into the phrases it can match.
+    pub fn expand_rule(&self, rule: &str, limits: &ExpansionLimits) -> Result<SrgsPhrases, Error> {
+        let phrases = Expander::new(&self.rules, limits)
+            .expand_rule(rule)?
+            .into_iter()
+            .map(|path| {
+                Ok(SrgsPhrase {
+                    text: path_text(&path),
+                    interpretation: interpret(&path, self.tag_format)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(SrgsPhrases { phrases })
+    }
+
+    /// Adds a rule, checking that it is not defined already.
+    fn add_rule(&mut self, name: &str, expansion: Expansion) -> Result<(), String> {
+        if self.rules.insert(name.to_owned(), expansion).is_some() {
+            Err(format!("rule {name:?} is defined more than once"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Phrases that an [`SrgsGrammar`] can match, returned by [`SrgsGrammar::expand`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrgsPhrases {
+    phrases: Vec<SrgsPhrase>,
+}
+
+impl SrgsPhrases {
+    /// Returns all the phrases, in the order they were expanded.
+    #[must_use]
+    pub fn phrases(&self) -> &[SrgsPhrase] {
+        &self.phrases
+    }
+
+    /// Returns the [`Grammar`] to pass to [`Recognizer::new_with_grammar`], without duplicates.
+    ///
+    /// [`Recognizer::new_with_grammar`]: crate::Recognizer::new_with_grammar
+    #[must_use]
+    pub fn grammar(&self) -> Grammar {
+        texts_grammar(self.phrases.iter().map(|phrase| phrase.text.as_str()))
+    }
+
+    /// Returns the first phrase whose text is `text`, ignoring differences in whitespace.
+    #[must_use]
+    pub fn find(&self, text: &str) -> Option<&SrgsPhrase> {
+        let text = normalize_text(text);
+        self.phrases.iter().find(|phrase| phrase.text == text)
+    }
+
+    /// Returns the semantic interpretation of a recognized `text`, or [`None`] if
+    /// the grammar does not match it. See [`find`](Self::find).
+    #[must_use]
+    pub fn interpret(&self, text: &str) -> Option<&Value> {
+        self.find(text).map(|phrase| &phrase.interpretation)
+    }
+}
+
+/// A phrase that an [`SrgsGrammar`] can match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrgsPhrase {
+    /// Text of the phrase, with words separated by a single space.
+    pub text: String,
+
+    /// Semantic interpretation of the phrase: the value of the root rule's `out`
+    /// variable, or the text of the phrase if the tags did not assign it.
+    pub interpretation: Value,
+}
+
+/// Returns an expansion that matches `expansion` between `min` and `max` times.
+fn repeat(expansion: Expansion, min: usize, max: Option<usize>) -> Expansion {
+    if min == 1 && max == Some(1) {
+        expansion
+    } else {
+        Expansion::Repeat {
+            inner: Box::new(expansion),
+            min,
+            max,
+        }
+    }
+}
+
+/// Parses a repeat specification (`n`, `n-m` or `n-`), shared by both forms.
+fn parse_repeat(spec: &str) -> Result<(usize, Option<usize>), String> {
+    let invalid = || format!("invalid repeat {spec:?}");
+
+    let (min, max) = match spec.split_once('-') {
+        Some((min, "")) => (min.trim().parse().map_err(|_| invalid())?, None),
+        Some((min, max)) => (
+            min.trim().parse().map_err(|_| invalid())?,
+            Some(max.trim().parse().map_err(|_| invalid())?),
+        ),
+        None => {
+            let count = spec.trim().parse().map_err(|_| invalid())?;
+            (count, Some(count))
+        }
+    };
+
+    if max.is_some_and(|max| max < min) {
+        return Err(invalid());
+    }
+
+    Ok((min, max))
+}
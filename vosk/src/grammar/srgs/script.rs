@@ -0,0 +1,543 @@
+use crate::{
+    grammar::{cursor::Cursor, expansion::Event},
+    Error,
+};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Format of the contents of tags, declared with `tag-format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TagFormat {
+    /// `semantics/1.0-literals`: the contents of a tag are the value of the rule it is in.
+    Literals,
+
+    /// `semantics/1.0`: the contents of a tag are a script. Only a subset of SISR is supported.
+    Script,
+}
+
+impl TagFormat {
+    pub(crate) fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim() {
+            "semantics/1.0-literals" => Ok(Self::Literals),
+            "semantics/1.0" | "semantics/1.0.2006" => Ok(Self::Script),
+            name => Err(format!("unsupported tag format {name:?}")),
+        }
+    }
+}
+
+/// Key of a property of an object or an element of an array.
+#[derive(Debug, Clone, PartialEq)]
+enum Key {
+    Field(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+
+    /// A property of `out`.
+    Out(Vec<Key>),
+
+    /// A property of the value of a rule, or of the latest rule if `name` is [`None`].
+    Rule {
+        name: Option<String>,
+        keys: Vec<Key>,
+    },
+
+    /// Text matched by a rule, or by the current rule if it is [`None`].
+    Text(Option<String>),
+
+    /// Operands of `+`.
+    Add(Vec<Expr>),
+}
+
+/// Assignment to `out` or one of its properties.
+#[derive(Debug, Clone, PartialEq)]
+struct Assignment {
+    target: Vec<Key>,
+    value: Expr,
+}
+
+/// Contents of a tag.
+///
+/// Scripts only support assignments to `out` and its properties, with string, number, boolean,
+/// `null`, `{}` and `[]` literals, `out`, `rules.<name>`, `rules.latest()`, `meta.current().text`
+/// and `meta.<name>.text` as values, and `+` to concatenate them.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Script(Vec<Assignment>);
+
+impl Script {
+    /// Parses the contents of a tag, from `cursor` until the end of its source.
+    pub(crate) fn parse(cursor: &mut Cursor, format: TagFormat) -> Result<Self, Error> {
+        if format == TagFormat::Literals {
+            return Ok(Self(vec![Assignment {
+                target: Vec::new(),
+                value: Expr::Literal(Value::String(cursor.rest().trim().to_owned())),
+            }]));
+        }
+
+        let mut assignments = Vec::new();
+        loop {
+            cursor.skip_trivia();
+            while cursor.eat(';') {
+                cursor.skip_trivia();
+            }
+            if cursor.is_eof() {
+                break;
+            }
+
+            if !cursor.eat_keyword("out") {
+                return Err(cursor.unexpected("an assignment to out"));
+            }
+            let target = parse_keys(cursor)?;
+            cursor.skip_trivia();
+            cursor.expect('=')?;
+            let value = parse_expr(cursor)?;
+
+            cursor.skip_trivia();
+            if !cursor.is_eof() && cursor.peek() != Some(';') {
+                return Err(cursor.unexpected("';'"));
+            }
+
+            assignments.push(Assignment { target, value });
+        }
+
+        Ok(Self(assignments))
+    }
+
+    fn run(&self, frame: &mut Frame) {
+        for Assignment { target, value } in &self.0 {
+            let value = frame.eval(value);
+            let out = frame.out.get_or_insert(Value::Null);
+            *property_mut(out, target) = value;
+        }
+    }
+}
+
+/// Returns the semantic interpretation of `path`, as an SRGS engine would.
+///
+/// The value of a rule is its `out` variable. If its tags did not assign it, SISR's default
+/// assignment applies: the value of the last rule it references if it ends with a reference,
+/// or the text it matched otherwise.
+pub(crate) fn interpret(path: &[Event], format: TagFormat) -> Result<Value, Error> {
+    // The first frame receives the value of the rule that was expanded
+    let mut stack = vec![Frame::default()];
+
+    for event in path {
+        let frame = stack.last_mut().expect("rule ends match rule starts");
+        match event {
+            Event::Word(word) => {
+                frame.words.push(word.clone());
+                frame.ends_with_rule = false;
+            }
+            Event::Tag(tag) => Script::parse(&mut Cursor::new(tag), format)?.run(frame),
+            Event::RuleStart(name) => stack.push(Frame {
+                name: name.clone(),
+                ..Frame::default()
+            }),
+            Event::RuleEnd => {
+                let frame = stack.pop().expect("rule ends match rule starts");
+                let parent = stack.last_mut().expect("rule ends match rule starts");
+
+                let text = frame.words.join(" ");
+                let value = match (frame.out, frame.latest) {
+                    (Some(out), _) => out,
+                    (None, Some(latest)) if frame.ends_with_rule => latest,
+                    (None, _) => Value::String(text.clone()),
+                };
+                parent.rules.insert(frame.name.clone(), value.clone());
+                parent.texts.insert(frame.name, text);
+                parent.latest = Some(value);
+                parent.ends_with_rule = true;
+                parent.words.extend(frame.words);
+            }
+        }
+    }
+
+    Ok(stack.swap_remove(0).latest.unwrap_or(Value::Null))
+}
+
+/// State of a rule while interpreting a path.
+#[derive(Debug, Default)]
+struct Frame {
+    name: String,
+    out: Option<Value>,
+
+    /// Words matched so far.
+    words: Vec<String>,
+
+    /// Values of the rules referenced so far.
+    rules: HashMap<String, Value>,
+
+    /// Texts matched by the rules referenced so far.
+    texts: HashMap<String, String>,
+
+    /// Value of the last rule referenced.
+    latest: Option<Value>,
+
+    /// Whether the last thing matched was a rule reference rather than a word.
+    ends_with_rule: bool,
+}
+
+impl Frame {
+    fn eval(&self, expr: &Expr) -> Value {
+        match expr {
+            Expr::Literal(value) => value.clone(),
+            Expr::Out(keys) => property(self.out.as_ref(), keys),
+            Expr::Rule { name, keys } => {
+                let value = match name {
+                    Some(name) => self.rules.get(name),
+                    None => self.latest.as_ref(),
+                };
+                property(value, keys)
+            }
+            Expr::Text(None) => Value::String(self.words.join(" ")),
+            Expr::Text(Some(name)) => self
+                .texts
+                .get(name)
+                .map_or(Value::Null, |text| Value::String(text.clone())),
+            Expr::Add(operands) => {
+                let values: Vec<_> = operands.iter().map(|operand| self.eval(operand)).collect();
+
+                // Like in JavaScript, numbers are added and anything else is concatenated
+                if values.iter().all(Value::is_number) {
+                    let sum = values.iter().filter_map(Value::as_f64).sum();
+                    number(sum)
+                } else {
+                    Value::String(
+                        values
+                            .iter()
+                            .map(|value| match value {
+                                Value::String(string) => string.clone(),
+                                value => value.to_string(),
+                            })
+                            .collect(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Returns the property of `value` at `keys`, or `null` if it does not exist.
+fn property(value: Option<&Value>, keys: &[Key]) -> Value {
+    value
+        .and_then(|value| {
+            keys.iter().try_fold(value, |value, key| match key {
+                Key::Field(field) => value.get(field),
+                Key::Index(index) => value.get(index),
+            })
+        })
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+/// Returns the property of `value` at `keys`, creating it and any
+/// objects or arrays that contain it if they do not exist.
+fn property_mut<'a>(value: &'a mut Value, keys: &[Key]) -> &'a mut Value {
+    keys.iter().fold(value, |value, key| match key {
+        Key::Field(field) => {
+            if !value.is_object() {
+                *value = Value::Object(Map::new());
+            }
+            value
+                .as_object_mut()
+                .unwrap()
+                .entry(field.clone())
+                .or_insert(Value::Null)
+        }
+        Key::Index(index) => {
+            if !value.is_array() {
+                *value = Value::Array(Vec::new());
+            }
+            let array = value.as_array_mut().unwrap();
+            if array.len() <= *index {
+                array.resize(index + 1, Value::Null);
+            }
+            &mut array[*index]
+        }
+    })
+}
+
+/// Returns `value` as a JSON number, which is an integer if possible.
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        Value::from(value as i64)
+    } else {
+        Number::from_f64(value).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// Parses `.name`, `["name"]` and `[index]` accessors.
+fn parse_keys(cursor: &mut Cursor) -> Result<Vec<Key>, Error> {
+    let mut keys = Vec::new();
+    loop {
+        if cursor.eat('.') {
+            keys.push(Key::Field(parse_identifier(cursor)?.to_owned()));
+        } else if cursor.eat('[') {
+            cursor.skip_trivia();
+            let key = match cursor.peek() {
+                Some('"' | '\'') => Key::Field(parse_string(cursor)?),
+                _ => {
+                    let index = cursor.take_while(|c| c.is_ascii_digit());
+                    Key::Index(
+                        index
+                            .parse()
+                            .map_err(|_| cursor.unexpected("a property name or an index"))?,
+                    )
+                }
+            };
+            cursor.skip_trivia();
+            cursor.expect(']')?;
+            keys.push(key);
+        } else {
+            return Ok(keys);
+        }
+    }
+}
+
+fn parse_identifier<'a>(cursor: &mut Cursor<'a>) -> Result<&'a str, Error> {
+    let identifier = cursor.take_while(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if identifier.is_empty() {
+        Err(cursor.unexpected("an identifier"))
+    } else {
+        Ok(identifier)
+    }
+}
+
+fn parse_expr(cursor: &mut Cursor) -> Result<Expr, Error> {
+    let mut operands = vec![parse_operand(cursor)?];
+    loop {
+        cursor.skip_trivia();
+        if !cursor.eat('+') {
+            break;
+        }
+        operands.push(parse_operand(cursor)?);
+    }
+
+    Ok(if operands.len() == 1 {
+        operands.remove(0)
+    } else {
+        Expr::Add(operands)
+    })
+}
+
+fn parse_operand(cursor: &mut Cursor) -> Result<Expr, Error> {
+    cursor.skip_trivia();
+    match cursor.peek() {
+        Some('"' | '\'') => Ok(Expr::Literal(Value::String(parse_string(cursor)?))),
+        Some(c) if c.is_ascii_digit() || c == '-' => {
+            let literal = cursor.take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '.'));
+            literal
+                .parse()
+                .map(|value| Expr::Literal(number(value)))
+                .map_err(|_| cursor.error(format!("invalid number {literal:?}")))
+        }
+        Some('{') => {
+            cursor.bump();
+            cursor.skip_trivia();
+            cursor.expect('}')?;
+            Ok(Expr::Literal(Value::Object(Map::new())))
+        }
+        Some('[') => {
+            cursor.bump();
+            cursor.skip_trivia();
+            cursor.expect(']')?;
+            Ok(Expr::Literal(Value::Array(Vec::new())))
+        }
+        Some('(') => {
+            cursor.bump();
+            let expr = parse_expr(cursor)?;
+            cursor.skip_trivia();
+            cursor.expect(')')?;
+            Ok(expr)
+        }
+        _ => match parse_identifier(cursor)? {
+            "true" => Ok(Expr::Literal(Value::Bool(true))),
+            "false" => Ok(Expr::Literal(Value::Bool(false))),
+            "null" | "undefined" => Ok(Expr::Literal(Value::Null)),
+            "out" => Ok(Expr::Out(parse_keys(cursor)?)),
+            "rules" => {
+                let name = if cursor.eat('.') {
+                    let name = parse_identifier(cursor)?;
+                    if name == "latest" && cursor.eat('(') {
+                        cursor.expect(')')?;
+                        None
+                    } else {
+                        Some(name.to_owned())
+                    }
+                } else {
+                    cursor.expect('[')?;
+                    cursor.skip_trivia();
+                    let name = parse_string(cursor)?;
+                    cursor.skip_trivia();
+                    cursor.expect(']')?;
+                    Some(name)
+                };
+
+                Ok(Expr::Rule {
+                    name,
+                    keys: parse_keys(cursor)?,
+                })
+            }
+            "meta" => {
+                cursor.expect('.')?;
+                let name = parse_identifier(cursor)?;
+                let name = if name == "current" && cursor.eat('(') {
+                    cursor.expect(')')?;
+                    None
+                } else {
+                    Some(name.to_owned())
+                };
+
+                cursor.expect('.')?;
+                if parse_identifier(cursor)? != "text" {
+                    return Err(cursor.error("only the text property of meta is supported"));
+                }
+
+                Ok(Expr::Text(name))
+            }
+            identifier => Err(cursor.error(format!("{identifier} is not supported in tags"))),
+        },
+    }
+}
+
+/// Parses a string literal delimited by single or double quotes.
+fn parse_string(cursor: &mut Cursor) -> Result<String, Error> {
+    let quote = match cursor.bump() {
+        Some(quote @ ('"' | '\'')) => quote,
+        _ => return Err(cursor.unexpected("a string")),
+    };
+
+    let mut string = String::new();
+    loop {
+        match cursor.bump() {
+            Some(c) if c == quote => return Ok(string),
+            Some('\\') => match cursor.bump() {
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some(c) => string.push(c),
+                None => return Err(cursor.unexpected(&format!("{quote:?}"))),
+            },
+            Some(c) => string.push(c),
+            None => return Err(cursor.unexpected(&format!("{quote:?}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        grammar::{srgs::SrgsGrammar, ExpansionLimits},
+        Error,
+    };
+    use serde_json::{json, Value};
+
+    /// Returns the interpretation of `text` by the ABNF grammar `rules`, whose root is `$a`.
+    fn interpret(rules: &str, text: &str) -> Value {
+        let grammar = SrgsGrammar::from_abnf(&format!("root $a;\n{rules}")).unwrap();
+        let phrases = grammar.expand(&ExpansionLimits::default()).unwrap();
+
+        phrases
+            .interpret(text)
+            .unwrap_or_else(|| panic!("{text:?} is not matched"))
+            .clone()
+    }
+
+    #[test]
+    fn rules_without_tags_are_their_text() {
+        assert_eq!(interpret("$a = x $b z; $b = y;", "x y z"), json!("x y z"));
+    }
+
+    #[test]
+    fn rules_ending_with_a_reference_are_its_value() {
+        assert_eq!(
+            interpret(r#"$a = $b; $b = y {out.v = "y";};"#, "y"),
+            json!({"v": "y"})
+        );
+        assert_eq!(interpret("$a = x $b; $b = y {out = 1;};", "x y"), json!(1));
+        // A word after the reference falls back to the text
+        assert_eq!(
+            interpret("$a = $b z; $b = y {out = 1;};", "y z"),
+            json!("y z")
+        );
+    }
+
+    #[test]
+    fn properties_and_rule_values_are_assigned() {
+        let rules = r#"
+            $a = $size $drink {
+                out.order.size = rules.size;
+                out.order["drink"] = rules.latest();
+                out.items[1] = meta.drink.text;
+                out.said = meta.current().text;
+            };
+            $size = small {out = "S";} | large {out = "L";};
+            $drink = tea | green tea;
+        "#;
+
+        assert_eq!(
+            interpret(rules, "large green tea"),
+            json!({
+                "order": {"size": "L", "drink": "green tea"},
+                "items": [null, "green tea"],
+                "said": "large green tea",
+            })
+        );
+    }
+
+    #[test]
+    fn values_are_added_or_concatenated() {
+        let rules = r#"
+            $a = $n $n {out.sum = rules.n + 1.5 + 2; out.text = "n" + '=' + rules.n + true;};
+            $n = one {out = 1;};
+        "#;
+
+        assert_eq!(
+            interpret(rules, "one one"),
+            json!({"sum": 4.5, "text": "n=1true"})
+        );
+    }
+
+    #[test]
+    fn literals_and_out_are_read() {
+        let rules = r#"
+            $a = go {!{
+                out = {};
+                out.list = [];
+                out.copy = out.list;
+                out.none = null;
+                out.flag = false;
+                out.quote = 'it\'s\n';
+            }!};
+        "#;
+
+        assert_eq!(
+            interpret(rules, "go"),
+            json!({"list": [], "copy": [], "none": null, "flag": false, "quote": "it's\n"})
+        );
+    }
+
+    #[test]
+    fn unsupported_scripts_are_rejected() {
+        for tag in [
+            "var x = 1;",
+            "out = foo;",
+            "out.a = ;",
+            "out = meta.current().score;",
+            "out = 'unterminated;",
+            "out = 1 out = 2",
+            "out = -;",
+        ] {
+            let source = format!("$a = a {{!{{ {tag} }}!}};");
+            assert!(
+                matches!(
+                    SrgsGrammar::from_abnf(&source),
+                    Err(Error::GrammarParse { .. })
+                ),
+                "{tag}"
+            );
+        }
+    }
+}
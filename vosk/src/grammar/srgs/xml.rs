@@ -0,0 +1,340 @@
+use super::{
+    parse_repeat, repeat,
+    script::{Script, TagFormat},
+    SrgsGrammar,
+};
+use crate::{
+    grammar::{cursor::Cursor, expansion::Expansion, UNK},
+    Error,
+};
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+/// Parses an SRGS grammar in the XML form.
+pub(super) fn parse(source: &str) -> Result<SrgsGrammar, Error> {
+    let document = Document::parse(source).map_err(|err| {
+        let position = err.pos();
+        Error::GrammarParse {
+            line: position.row as usize,
+            column: position.col as usize,
+            message: err.to_string(),
+        }
+    })?;
+    let parser = Parser {
+        document: &document,
+    };
+
+    let root = document.root_element();
+    if !root.has_tag_name("grammar") {
+        return Err(parser.error(root, "expected a <grammar> root element"));
+    }
+    if root.attribute("mode").is_some_and(|mode| mode != "voice") {
+        return Err(parser.error(root, "only voice grammars are supported"));
+    }
+
+    let mut grammar = SrgsGrammar {
+        root: root.attribute("root").map(str::to_owned),
+        rules: HashMap::new(),
+        tag_format: root
+            .attribute("tag-format")
+            .map(TagFormat::from_name)
+            .transpose()
+            .map_err(|message| parser.error(root, message))?
+            .unwrap_or(TagFormat::Script),
+    };
+
+    for node in root.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "rule" => {
+                let name = node
+                    .attribute("id")
+                    .ok_or_else(|| parser.error(node, "rule does not have an id"))?;
+                let expansion = parser.parse_sequence(node, grammar.tag_format)?;
+
+                grammar
+                    .add_rule(name, expansion)
+                    .map_err(|message| parser.error(node, message))?;
+            }
+            // Global tags are only used to initialize variables, which are not supported
+            "lexicon" | "meta" | "metadata" | "tag" => {}
+            name => return Err(parser.error(node, format!("unexpected element <{name}>"))),
+        }
+    }
+
+    Ok(grammar)
+}
+
+struct Parser<'a, 'input> {
+    document: &'a Document<'input>,
+}
+
+impl Parser<'_, '_> {
+    /// Parses the children of `node` as a sequence.
+    fn parse_sequence(&self, node: Node, format: TagFormat) -> Result<Expansion, Error> {
+        let mut sequence = Vec::new();
+
+        for child in node.children() {
+            if let Some(text) = child.text().filter(|_| child.is_text()) {
+                // Quotes group words into a single token, but Vosk only cares about the words
+                let text = text.replace('"', " ");
+                if !text.trim().is_empty() {
+                    sequence.push(Expansion::Token(text));
+                }
+                continue;
+            }
+            if !child.is_element() {
+                continue;
+            }
+
+            let expansion = match child.tag_name().name() {
+                "item" => self.parse_item(child, format)?,
+                "one-of" => self.parse_one_of(child, format)?,
+                "ruleref" => self.parse_ruleref(child)?,
+                "token" => Expansion::Token(text_content(child)),
+                "tag" => {
+                    // Untrimmed, so that errors point to the right position
+                    let text = raw_text(child);
+                    let start = child.first_child().unwrap_or(child);
+                    let position = self.document.text_pos_at(start.range().start);
+                    Script::parse(
+                        &mut Cursor::at(&text, position.row as usize, position.col as usize),
+                        format,
+                    )?;
+
+                    Expansion::Tag(text.trim().to_owned())
+                }
+                "example" | "lexicon" | "meta" | "metadata" => continue,
+                name => return Err(self.error(child, format!("unexpected element <{name}>"))),
+            };
+            sequence.push(expansion);
+        }
+
+        Ok(Expansion::sequence(sequence))
+    }
+
+    /// Parses an `<item>`, repeated as set by its `repeat` attribute.
+    fn parse_item(&self, node: Node, format: TagFormat) -> Result<Expansion, Error> {
+        let expansion = self.parse_sequence(node, format)?;
+        match node.attribute("repeat") {
+            Some(spec) => {
+                let (min, max) = parse_repeat(spec).map_err(|message| self.error(node, message))?;
+                Ok(repeat(expansion, min, max))
+            }
+            None => Ok(expansion),
+        }
+    }
+
+    fn parse_one_of(&self, node: Node, format: TagFormat) -> Result<Expansion, Error> {
+        let mut alternatives = Vec::new();
+
+        for child in node.children() {
+            if child.is_element() && child.has_tag_name("item") {
+                // Weights only matter for the probability of each alternative,
+                // which Vosk does not support, so they are ignored
+                alternatives.push(self.parse_item(child, format)?);
+            } else if child.is_element() || child.text().is_some_and(|text| !text.trim().is_empty())
+            {
+                return Err(self.error(child, "<one-of> can only contain <item> elements"));
+            }
+        }
+
+        if alternatives.is_empty() {
+            return Err(self.error(node, "<one-of> does not contain any <item> elements"));
+        }
+
+        Ok(Expansion::alternatives(alternatives))
+    }
+
+    fn parse_ruleref(&self, node: Node) -> Result<Expansion, Error> {
+        if let Some(uri) = node.attribute("uri") {
+            return match uri.strip_prefix('#') {
+                Some(name) => Ok(Expansion::RuleRef(name.to_owned())),
+                None => Err(self.error(node, "external rule references are not supported")),
+            };
+        }
+
+        match node.attribute("special") {
+            Some("NULL") => Ok(Expansion::Null),
+            Some("VOID") => Ok(Expansion::Void),
+            Some("GARBAGE") => Ok(Expansion::Token(UNK.to_owned())),
+            Some(special) => Err(self.error(node, format!("unknown special rule {special:?}"))),
+            None => Err(self.error(node, "<ruleref> needs a uri or special attribute")),
+        }
+    }
+
+    /// Returns a parse error at the start of `node`.
+    fn error(&self, node: Node, message: impl Into<String>) -> Error {
+        let position = self.document.text_pos_at(node.range().start);
+        Error::GrammarParse {
+            line: position.row as usize,
+            column: position.col as usize,
+            message: message.into(),
+        }
+    }
+}
+
+/// Returns all the text inside of `node`.
+fn raw_text(node: Node) -> String {
+    node.descendants()
+        .filter_map(|node| node.text().filter(|_| node.is_text()))
+        .collect()
+}
+
+/// Returns all the text inside of `node`, without surrounding whitespace.
+fn text_content(node: Node) -> String {
+    raw_text(node).trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        grammar::{srgs::SrgsGrammar, ExpansionLimits},
+        Error,
+    };
+    use serde_json::json;
+
+    fn texts(source: &str) -> Vec<String> {
+        let grammar = SrgsGrammar::from_xml(source).unwrap();
+        let mut texts: Vec<_> = grammar
+            .expand(&ExpansionLimits::default())
+            .unwrap()
+            .phrases()
+            .iter()
+            .map(|phrase| phrase.text.clone())
+            .collect();
+        texts.sort();
+        texts
+    }
+
+    #[test]
+    fn items_are_repeated() {
+        let texts = texts(
+            r##"<grammar root="drink" mode="voice">
+                <rule id="drink">
+                    <item repeat="0-1">a</item>
+                    <one-of>
+                        <item repeat="1-2">pepsi</item>
+                        <item weight="2">cola</item>
+                    </one-of>
+                </rule>
+            </grammar>"##,
+        );
+
+        assert_eq!(
+            texts,
+            [
+                "a cola",
+                "a pepsi",
+                "a pepsi pepsi",
+                "cola",
+                "pepsi",
+                "pepsi pepsi"
+            ]
+        );
+    }
+
+    #[test]
+    fn rule_references_tokens_and_special_rules() {
+        let texts = texts(
+            r##"<grammar root="main">
+                <rule id="main">
+                    <ruleref uri="#number"/> <token>new york</token>
+                    <ruleref special="NULL"/>
+                    <one-of>
+                        <item>now</item>
+                        <item><ruleref special="VOID"/> never</item>
+                        <item><ruleref special="GARBAGE"/></item>
+                    </one-of>
+                </rule>
+                <rule id="number"><one-of><item>one</item><item>two</item></one-of></rule>
+            </grammar>"##,
+        );
+
+        assert_eq!(
+            texts,
+            [
+                "one new york [unk]",
+                "one new york now",
+                "two new york [unk]",
+                "two new york now"
+            ]
+        );
+    }
+
+    #[test]
+    fn tags_are_interpreted() {
+        let grammar = SrgsGrammar::from_xml(
+            r##"<grammar root="order" tag-format="semantics/1.0">
+                <rule id="order">
+                    <ruleref uri="#size"/> coffee <tag>out.size = rules.size;</tag>
+                </rule>
+                <rule id="size">
+                    <one-of>
+                        <item>small <tag>out = "S";</tag></item>
+                        <item>large <tag>out = "L";</tag></item>
+                    </one-of>
+                </rule>
+            </grammar>"##,
+        )
+        .unwrap();
+        let phrases = grammar.expand(&ExpansionLimits::default()).unwrap();
+
+        assert_eq!(
+            phrases.interpret("large  coffee"),
+            Some(&json!({"size": "L"}))
+        );
+        assert_eq!(phrases.interpret("medium coffee"), None);
+    }
+
+    #[test]
+    fn literal_tags_are_the_value_of_the_rule() {
+        let grammar = SrgsGrammar::from_xml(
+            r##"<grammar root="answer" tag-format="semantics/1.0-literals">
+                <rule id="answer">
+                    <one-of>
+                        <item>yes <tag>true</tag></item>
+                        <item>no <tag>false</tag></item>
+                    </one-of>
+                </rule>
+            </grammar>"##,
+        )
+        .unwrap();
+        let phrases = grammar.expand(&ExpansionLimits::default()).unwrap();
+
+        assert_eq!(phrases.interpret("yes"), Some(&json!("true")));
+    }
+
+    #[test]
+    fn invalid_grammars_are_rejected() {
+        let error = |source: &str| match SrgsGrammar::from_xml(source) {
+            Err(Error::GrammarParse {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            other => panic!("unexpected result {other:?}"),
+        };
+
+        let (line, _, message) = error("<grammar>\n  <rule id=\"a\"><foo/></rule>\n</grammar>");
+        assert_eq!(line, 2);
+        assert!(message.contains("<foo>"), "{message}");
+
+        let (_, _, message) = error(r##"<grammar mode="dtmf"><rule id="a">1</rule></grammar>"##);
+        assert!(message.contains("voice"), "{message}");
+
+        let (_, _, message) =
+            error(r##"<grammar><rule id="a"><item repeat="3-1">a</item></rule></grammar>"##);
+        assert!(message.contains("repeat"), "{message}");
+
+        let (line, column, _) =
+            error("<grammar>\n<rule id=\"a\">a <tag>\n out = foo;</tag></rule></grammar>");
+        // Errors in tags point to their position in the document
+        assert_eq!((line, column), (3, 11));
+
+        let (_, _, message) =
+            error(r##"<grammar><rule id="a"><ruleref uri="other.grxml#b"/></rule></grammar>"##);
+        assert!(message.contains("external"), "{message}");
+
+        assert!(SrgsGrammar::from_xml("<grammar>").is_err());
+    }
+}
@@ -6,12 +6,49 @@
 //! * Create a [`Recognizer`] with that model
 //! * Feel audio to the recognizer with [`Recognizer::accept_waveform`]
 //! * Get the processed result with [`Recognizer::result`],
-//! [`Recognizer::partial_result`] or [`Recognizer::final_result`]
+//!   [`Recognizer::partial_result`] or [`Recognizer::final_result`]
 
+mod backend;
+mod channels;
+mod endpointer;
+mod error;
+pub mod export;
+mod gpu;
+pub mod grammar;
+#[cfg(feature = "dynamic-load")]
+pub mod library;
 mod log;
+#[cfg(feature = "mock")]
+pub mod mock;
+mod model_info;
 mod models;
 mod recognition;
+mod replay;
+mod resampling;
+mod sample;
+mod session;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "vosk-0-3-50")]
+mod text_processor;
+mod timeline;
 
+pub use backend::*;
+pub use channels::*;
+pub use endpointer::*;
+pub use error::*;
+pub use gpu::*;
+pub use grammar::Grammar;
 pub use log::*;
+pub use model_info::*;
 pub use models::*;
 pub use recognition::*;
+pub use replay::*;
+pub use resampling::*;
+pub use sample::*;
+pub use session::*;
+#[cfg(feature = "async")]
+pub use stream::*;
+#[cfg(feature = "vosk-0-3-50")]
+pub use text_processor::*;
+pub use timeline::*;
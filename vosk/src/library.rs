@@ -0,0 +1,71 @@
+//! Loading the Vosk library at runtime, with the `dynamic-load` feature.
+//!
+//! The library is loaded from the [`default_search_paths`] the first time it is needed,
+//! unless it has been loaded before with [`load`] or [`load_from`]. Loading it explicitly
+//! at startup allows shipping it in a custom location and reporting a missing library early.
+//!
+//! [`Model::new`](crate::Model::new) and the other constructors return
+//! [`Error::Library`] when it cannot be loaded, while functions that cannot
+//! report errors, like [`gpu_init`](crate::gpu_init), panic.
+//!
+//! Calling the functions of `vosk_sys` directly before the library is
+//! loaded aborts the process instead if it cannot be found.
+
+use crate::Error;
+use std::{ffi::OsStr, path::PathBuf};
+
+pub use vosk_sys::{LoadError, LIBRARY_NAME};
+
+/// Loads the Vosk library from `path`, which can be either a path
+/// to the library or a file name for the system's loader to look for.
+///
+/// Fails with [`LoadError::AlreadyLoaded`] if a library has already been loaded.
+pub fn load(path: impl AsRef<OsStr>) -> Result<(), LoadError> {
+    vosk_sys::load(path)
+}
+
+/// Loads the Vosk library from the first of `paths` that can be opened.
+pub fn load_from<I>(paths: I) -> Result<(), LoadError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<OsStr>,
+{
+    vosk_sys::load_from(paths)
+}
+
+/// Returns the paths the library is loaded from if it has not been loaded explicitly.
+///
+/// See [`vosk_sys::default_search_paths`] for the order they are tried in.
+#[must_use]
+pub fn default_search_paths() -> Vec<PathBuf> {
+    vosk_sys::default_search_paths()
+}
+
+/// Returns whether the Vosk library has been loaded.
+#[must_use]
+pub fn is_loaded() -> bool {
+    vosk_sys::is_loaded()
+}
+
+/// Loads the library from the [`default_search_paths`] if it has not been loaded yet.
+pub(crate) fn ensure_loaded() -> Result<(), Error> {
+    if is_loaded() {
+        return Ok(());
+    }
+
+    match load_from(default_search_paths()) {
+        Ok(()) | Err(LoadError::AlreadyLoaded) => Ok(()),
+        Err(err) => Err(Error::Library(err)),
+    }
+}
+
+/// Loads the library like [`ensure_loaded`], for functions that cannot return errors.
+///
+/// # Panics
+///
+/// Panics if the library cannot be loaded.
+pub(crate) fn expect_loaded() {
+    if let Err(err) = ensure_loaded() {
+        panic!("{err}");
+    }
+}
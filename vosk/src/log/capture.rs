@@ -0,0 +1,320 @@
+/// Target of the records emitted for Kaldi's messages.
+pub const KALDI_LOG_TARGET: &str = "vosk::kaldi";
+
+/// Severity of a message printed by Kaldi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KaldiLevel {
+    /// Printed with the `ERROR` or `ASSERTION_FAILED` prefix.
+    Error,
+
+    /// Printed with the `WARNING` prefix.
+    Warning,
+
+    /// Printed with the `LOG` prefix.
+    Info,
+
+    /// Printed with the `VLOG[n]` prefix, which is only printed if
+    /// the verbosity is at least `n` (see [`set_log_level`](crate::set_log_level)).
+    Verbose(u8),
+}
+
+/// A message printed by Kaldi to stderr, like:
+///
+/// ```text
+/// LOG (VoskAPI:ReadDataFiles():model.cc:213) Decoding params beam=13 max-active=7000
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KaldiMessage<'a> {
+    /// Severity of the message.
+    pub level: KaldiLevel,
+
+    /// Component that printed the message, which is the name Kaldi was
+    /// given by the program, like `VoskAPI`.
+    pub component: &'a str,
+
+    /// Function that printed the message, like `ReadDataFiles()`.
+    pub function: &'a str,
+
+    /// Source file that printed the message, like `model.cc`.
+    pub file: &'a str,
+
+    /// Line of [`file`](Self::file) that printed the message.
+    pub line: u32,
+
+    /// The message itself.
+    pub message: &'a str,
+}
+
+impl<'a> KaldiMessage<'a> {
+    /// Parses a line printed by Kaldi, returning [`None`] if it is not one of its messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use vosk::{KaldiLevel, KaldiMessage};
+    /// #
+    /// let message =
+    ///     KaldiMessage::parse("WARNING (VoskAPI:Compute():model.cc:104) Missing file").unwrap();
+    /// assert_eq!(message.level, KaldiLevel::Warning);
+    /// assert_eq!(message.function, "Compute()");
+    /// assert_eq!(message.line, 104);
+    /// assert_eq!(message.message, "Missing file");
+    ///
+    /// assert_eq!(KaldiMessage::parse("Something else"), None);
+    /// ```
+    #[must_use]
+    pub fn parse(line: &'a str) -> Option<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (prefix, rest) = line.split_once(" (")?;
+
+        let level = match prefix {
+            "ERROR" | "ASSERTION_FAILED" => KaldiLevel::Error,
+            "WARNING" => KaldiLevel::Warning,
+            "LOG" => KaldiLevel::Info,
+            _ => KaldiLevel::Verbose(
+                prefix
+                    .strip_prefix("VLOG[")?
+                    .strip_suffix(']')?
+                    .parse()
+                    .ok()?,
+            ),
+        };
+
+        // The location has no spaces, unlike the message
+        let (location, message) = match rest.split_once(") ") {
+            Some(parts) => parts,
+            None => (rest.strip_suffix(')')?, ""),
+        };
+        let mut parts = location.rsplitn(3, ':');
+        let line = parts.next()?.parse().ok()?;
+        let file = parts.next()?;
+        let (component, function) = parts.next()?.split_once(':')?;
+
+        Some(Self {
+            level,
+            component,
+            function,
+            file,
+            line,
+            message,
+        })
+    }
+
+    /// Emits the message as a `tracing` event, or a `log` record
+    /// without the `tracing` feature, with the [`KALDI_LOG_TARGET`] target.
+    ///
+    /// The function, file and line are fields of the `tracing` event. With `log`, the function
+    /// is added before the message, as the file and line are the only metadata of its records.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    pub fn emit(&self) {
+        #[cfg(feature = "tracing")]
+        {
+            macro_rules! event {
+                ($level:expr) => {
+                    ::tracing::event!(
+                        target: KALDI_LOG_TARGET,
+                        $level,
+                        component = self.component,
+                        function = self.function,
+                        file = self.file,
+                        line = self.line,
+                        "{}",
+                        self.message
+                    )
+                };
+            }
+
+            match self.level {
+                KaldiLevel::Error => event!(::tracing::Level::ERROR),
+                KaldiLevel::Warning => event!(::tracing::Level::WARN),
+                KaldiLevel::Info => event!(::tracing::Level::INFO),
+                KaldiLevel::Verbose(1) => event!(::tracing::Level::DEBUG),
+                KaldiLevel::Verbose(_) => event!(::tracing::Level::TRACE),
+            }
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let level = match self.level {
+                KaldiLevel::Error => ::log::Level::Error,
+                KaldiLevel::Warning => ::log::Level::Warn,
+                KaldiLevel::Info => ::log::Level::Info,
+                KaldiLevel::Verbose(1) => ::log::Level::Debug,
+                KaldiLevel::Verbose(_) => ::log::Level::Trace,
+            };
+            if level > ::log::max_level() {
+                return;
+            }
+
+            ::log::logger().log(
+                &::log::Record::builder()
+                    .args(format_args!("{}: {}", self.function, self.message))
+                    .level(level)
+                    .target(KALDI_LOG_TARGET)
+                    .file(Some(self.file))
+                    .line(Some(self.line))
+                    .build(),
+            );
+        }
+    }
+}
+
+#[cfg(all(unix, any(feature = "log", feature = "tracing")))]
+pub use redirect::*;
+
+#[cfg(all(unix, any(feature = "log", feature = "tracing")))]
+mod redirect {
+    use super::KaldiMessage;
+    use std::{
+        fs::File,
+        io::{self, BufRead, BufReader, Write},
+        os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc::{self, Receiver, Sender},
+        },
+        time::Duration,
+    };
+
+    /// Whether stderr is being captured, as it can only be redirected once.
+    static CAPTURING: AtomicBool = AtomicBool::new(false);
+
+    /// How long dropping a [`StderrCapture`] waits for the messages written before to be emitted.
+    const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Redirects the process' stderr to a pipe, and emits Kaldi's messages
+    /// written to it as `tracing` events or `log` records (see [`KaldiMessage::emit`]),
+    /// until the returned [`StderrCapture`] is dropped.
+    ///
+    /// The lines that are not Kaldi messages are written to the original stderr unchanged.
+    ///
+    /// Loggers that write to stderr, like `env_logger`, write the emitted records to the pipe too.
+    /// They are not Kaldi messages, so they are passed through to the original stderr. The pipe
+    /// is drained by a different thread than the one that emits the messages, so it never fills
+    /// up while a record is being written to it.
+    ///
+    /// Only available on Unix, with the `log` or `tracing` feature. With both of them,
+    /// events are only emitted with `tracing`, which can forward them to `log`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::AlreadyExists`] if stderr is already
+    /// being captured, or if the pipe or the thread cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vosk::Model;
+    /// #
+    /// let capture = vosk::capture_stderr().expect("Could not capture stderr");
+    /// let model = Model::new("/path/to/model").expect("Could not create a model");
+    /// // Restores stderr once the model has been loaded
+    /// drop(capture);
+    /// ```
+    pub fn capture_stderr() -> io::Result<StderrCapture> {
+        if CAPTURING.swap(true, Ordering::AcqRel) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "stderr is already being captured",
+            ));
+        }
+
+        StderrCapture::start().inspect_err(|_| CAPTURING.store(false, Ordering::Release))
+    }
+
+    /// Captures the process' stderr while it is alive, see [`capture_stderr`].
+    ///
+    /// Dropping it restores the original stderr and waits until everything written before has
+    /// been emitted. Child processes spawned while capturing inherit the pipe, which is only
+    /// drained until they exit, so the wait is bounded in case they are still running.
+    #[must_use = "stderr is restored when the capture is dropped"]
+    pub struct StderrCapture {
+        /// The original stderr, which is restored on drop.
+        original: OwnedFd,
+
+        /// Disconnected once everything written to the pipe has been emitted.
+        drained: Receiver<()>,
+    }
+
+    impl StderrCapture {
+        fn start() -> io::Result<Self> {
+            let mut fds: [RawFd; 2] = [0; 2];
+            check(unsafe { libc::pipe(fds.as_mut_ptr()) })?;
+            // SAFETY: pipe returned two new file descriptors
+            let (read_end, write_end) =
+                unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+
+            let original = unsafe { OwnedFd::from_raw_fd(check(libc::dup(libc::STDERR_FILENO))?) };
+            let passthrough = File::from(original.try_clone()?);
+
+            // The threads are detached, they stop once every write end of the pipe is closed
+            let (messages, received) = mpsc::channel();
+            let (drained_sender, drained) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("vosk-stderr-emit".to_owned())
+                .spawn(move || emit_all(received, drained_sender))?;
+            std::thread::Builder::new()
+                .name("vosk-stderr".to_owned())
+                .spawn(move || {
+                    forward(BufReader::new(File::from(read_end)), passthrough, messages)
+                })?;
+
+            let _ = io::stderr().flush();
+            check(unsafe { libc::dup2(write_end.as_raw_fd(), libc::STDERR_FILENO) })?;
+            // stderr is now the only write end, so the reader stops once it is restored
+            drop(write_end);
+
+            Ok(Self { original, drained })
+        }
+    }
+
+    impl Drop for StderrCapture {
+        fn drop(&mut self) {
+            let _ = io::stderr().flush();
+            unsafe { libc::dup2(self.original.as_raw_fd(), libc::STDERR_FILENO) };
+
+            let _ = self.drained.recv_timeout(DRAIN_TIMEOUT);
+            CAPTURING.store(false, Ordering::Release);
+        }
+    }
+
+    /// Sends the messages read from `reader` to `messages` and writes everything else to
+    /// `passthrough`, which never blocks on the thread that emits them.
+    fn forward(mut reader: impl BufRead, mut passthrough: File, messages: Sender<String>) {
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+
+            let text = String::from_utf8_lossy(&line);
+            if KaldiMessage::parse(&text).is_some() {
+                let _ = messages.send(text.into_owned());
+            } else {
+                let _ = passthrough.write_all(&line);
+            }
+        }
+    }
+
+    /// Emits the messages received until the reader stops, and then disconnects `drained`.
+    fn emit_all(messages: Receiver<String>, drained: Sender<()>) {
+        for line in messages {
+            if let Some(message) = KaldiMessage::parse(&line) {
+                message.emit();
+            }
+        }
+
+        drop(drained);
+    }
+
+    /// Turns the return value of a libc function into an [`io::Result`].
+    fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(result)
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use std::os::raw::c_int;
 use vosk_sys::*;
 
+pub use capture::*;
+
+mod capture;
+
 #[derive(Debug, Default, Clone, Copy)]
 /// Log level for Kaldi messages.
 pub enum LogLevel {
@@ -28,6 +32,13 @@ impl LogLevel {
 /// Set log level for Kaldi messages.
 ///
 /// Default: [`LogLevel::ErrorInfo`].
+///
+/// # Panics
+///
+/// With the `dynamic-load` feature, panics if the Vosk library cannot be loaded.
 pub fn set_log_level(log_level: LogLevel) {
+    #[cfg(feature = "dynamic-load")]
+    crate::library::expect_loaded();
+
     unsafe { vosk_set_log_level(log_level.to_c_int()) }
 }
@@ -0,0 +1,65 @@
+//! A mock of the Vosk library for testing without the library or a model, with the `mock` feature.
+//!
+//! See [`vosk_sys::mock`] for how the mock is scripted. [`Model::new`] still reads the model
+//! directory before passing it to the mock, so one can be created with [`write_model_dir`].
+//!
+//! # Examples
+//!
+//! ```
+//! use vosk::mock::{text_json, write_model_dir, Mock, MockRecognizer};
+//! use vosk::{DecodingState, GraphKind, Model, Recognizer};
+//!
+//! let dir = std::env::temp_dir().join("vosk-mock-example");
+//! write_model_dir(&dir, GraphKind::Lookahead).unwrap();
+//!
+//! let _guard = Mock::new()
+//!     .recognizer(MockRecognizer::new().finalized(text_json("hello world")))
+//!     .install();
+//!
+//! let model = Model::new(&dir).unwrap();
+//! let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+//!
+//! assert_eq!(recognizer.accept_waveform(&[0; 1600]), DecodingState::Finalized);
+//! assert_eq!(recognizer.result().single().unwrap().text, "hello world");
+//! ```
+//!
+//! [`Model::new`]: crate::Model::new
+
+use crate::GraphKind;
+use std::{fs, io, path::Path};
+
+pub use vosk_sys::mock::*;
+
+/// Creates the files that [`Model::new`] and [`ModelInfo::from_dir`] need in `dir`,
+/// for a model with a `graph` decoding graph and a sample rate of 16 kHz.
+///
+/// The files are empty except for the configuration, so they can only be loaded by the mock.
+///
+/// [`Model::new`]: crate::Model::new
+/// [`ModelInfo::from_dir`]: crate::ModelInfo::from_dir
+pub fn write_model_dir(dir: impl AsRef<Path>, graph: GraphKind) -> io::Result<()> {
+    let dir = dir.as_ref();
+    for subdir in ["am", "conf", "graph"] {
+        fs::create_dir_all(dir.join(subdir))?;
+    }
+
+    fs::write(dir.join("am/final.mdl"), "")?;
+    fs::write(
+        dir.join("conf/mfcc.conf"),
+        "--sample-frequency=16000\n--use-energy=false\n",
+    )?;
+    fs::write(
+        dir.join("conf/model.conf"),
+        "--frame-subsampling-factor=3\n",
+    )?;
+
+    let graph_files: &[&str] = match graph {
+        GraphKind::Static => &["graph/HCLG.fst"],
+        GraphKind::Lookahead => &["graph/HCLr.fst", "graph/Gr.fst"],
+    };
+    for file in graph_files {
+        fs::write(dir.join(file), "")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use crate::Error;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Type of decoding graph of a [`Model`].
+///
+/// [`Model`]: crate::Model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    /// Precompiled static graph (`HCLG.fst`). Usually found in big server models.
+    Static,
+
+    /// Lookahead graph (`HCLr.fst` and `Gr.fst`), which is composed at runtime.
+    /// Usually found in small models.
+    Lookahead,
+}
+
+/// Features supported by a [`Model`], returned by [`Model::capabilities`].
+///
+/// [`Model`]: crate::Model
+/// [`Model::capabilities`]: crate::Model::capabilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    /// Whether the model can be restricted to a list of phrases with
+    /// [`Recognizer::new_with_grammar`]. Only models with a [`GraphKind::Lookahead`] graph support it.
+    ///
+    /// [`Recognizer::new_with_grammar`]: crate::Recognizer::new_with_grammar
+    pub supports_runtime_grammar: bool,
+
+    /// Whether the model contains an i-vector extractor, used to adapt the recognition to the speaker.
+    pub has_speaker_ivectors: bool,
+
+    /// Whether the model can rescore results with an RNNLM.
+    pub has_rnnlm_rescoring: bool,
+
+    /// Whether the model contains word boundary information, which is needed for
+    /// the word times in results (see [`Recognizer::set_words`]).
+    ///
+    /// [`Recognizer::set_words`]: crate::Recognizer::set_words
+    pub has_word_boundaries: bool,
+}
+
+/// Configuration of a [`Model`], read from the files in its directory.
+///
+/// Options that are not present in the configuration files have Kaldi's default values,
+/// or [`None`] if Vosk chooses the value at runtime.
+///
+/// [`Model`]: crate::Model
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelInfo {
+    /// Sample rate of the audio the model was trained with (`--sample-frequency` in `mfcc.conf`).
+    pub sample_frequency: f32,
+
+    /// Time between two consecutive feature frames (`--frame-shift` in `mfcc.conf`).
+    pub frame_shift: Duration,
+
+    /// Amount of feature frames per decoded frame (`--frame-subsampling-factor` in `model.conf`).
+    pub frame_subsampling_factor: u32,
+
+    /// Decoding beam (`--beam` in `model.conf`).
+    pub beam: Option<f32>,
+
+    /// Lattice generation beam (`--lattice-beam` in `model.conf`).
+    pub lattice_beam: Option<f32>,
+
+    /// Minimum amount of active states while decoding (`--min-active` in `model.conf`).
+    pub min_active: Option<u32>,
+
+    /// Maximum amount of active states while decoding (`--max-active` in `model.conf`).
+    pub max_active: Option<u32>,
+
+    /// Type of decoding graph.
+    pub graph: GraphKind,
+
+    /// Whether the model contains an i-vector extractor (`ivector/`).
+    pub has_ivector: bool,
+
+    /// Whether the model contains word boundary information (`phones/word_boundary.int`).
+    pub has_word_boundaries: bool,
+
+    /// Whether the model contains an RNNLM for rescoring (`rnnlm/`).
+    pub has_rnnlm: bool,
+
+    /// Whether the model contains a constant arpa language model for rescoring (`rescore/G.carpa`).
+    pub has_carpa: bool,
+}
+
+impl ModelInfo {
+    /// Reads the configuration of the model in `model_path`, without loading it.
+    ///
+    /// Both the current model layout (`am/`, `conf/`, `graph/`) and the legacy flat layout
+    /// are supported.
+    pub fn from_dir(model_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = model_path.as_ref();
+        if !dir.is_dir() {
+            return Err(Error::PathNotFound(dir.to_path_buf()));
+        }
+
+        let mfcc_path = find_file(dir, &["conf/mfcc.conf", "mfcc.conf"]).ok_or_else(|| {
+            Error::MissingModelFile {
+                file: PathBuf::from("conf/mfcc.conf"),
+            }
+        })?;
+        let mfcc = ConfigFile::read(dir, &mfcc_path)?;
+
+        // model.conf is optional, Vosk uses default values if it is missing
+        let model = match find_file(dir, &["conf/model.conf", "model.conf"]) {
+            Some(model_path) => ConfigFile::read(dir, &model_path)?,
+            None => ConfigFile::default(),
+        };
+
+        let graph = if find_file(dir, &["graph/HCLG.fst", "HCLG.fst"]).is_some() {
+            GraphKind::Static
+        } else if find_file(dir, &["graph/HCLr.fst", "HCLr.fst"]).is_some() {
+            if find_file(dir, &["graph/Gr.fst", "Gr.fst"]).is_none() {
+                return Err(Error::MissingModelFile {
+                    file: PathBuf::from("graph/Gr.fst"),
+                });
+            }
+
+            GraphKind::Lookahead
+        } else {
+            return Err(Error::MissingModelFile {
+                file: PathBuf::from("graph/HCLG.fst"),
+            });
+        };
+
+        let frame_shift_ms = mfcc.parse_positive("frame-shift")?.unwrap_or(10.0);
+        let frame_shift = Duration::try_from_secs_f32(frame_shift_ms / 1000.0).map_err(|_| {
+            Error::InvalidModelConfig {
+                file: mfcc.file.clone(),
+                reason: format!("invalid value {frame_shift_ms} for --frame-shift"),
+            }
+        })?;
+
+        Ok(Self {
+            sample_frequency: mfcc.parse_positive("sample-frequency")?.unwrap_or(16000.0),
+            frame_shift,
+            frame_subsampling_factor: model.parse("frame-subsampling-factor")?.unwrap_or(1),
+            beam: model.parse("beam")?,
+            lattice_beam: model.parse("lattice-beam")?,
+            min_active: model.parse("min-active")?,
+            max_active: model.parse("max-active")?,
+            graph,
+            has_ivector: find_file(dir, &["ivector/final.ie"]).is_some(),
+            has_word_boundaries: find_file(
+                dir,
+                &["graph/phones/word_boundary.int", "word_boundary.int"],
+            )
+            .is_some(),
+            has_rnnlm: find_file(dir, &["rnnlm/final.raw"]).is_some(),
+            has_carpa: find_file(dir, &["rescore/G.carpa"]).is_some(),
+        })
+    }
+
+    /// Returns the features supported by the model.
+    #[must_use]
+    pub fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            supports_runtime_grammar: self.graph == GraphKind::Lookahead,
+            has_speaker_ivectors: self.has_ivector,
+            has_rnnlm_rescoring: self.has_rnnlm,
+            has_word_boundaries: self.has_word_boundaries,
+        }
+    }
+
+    /// Returns the time between two consecutive decoded frames, which is the
+    /// resolution of the word times in the results.
+    #[must_use]
+    pub fn decoded_frame_shift(&self) -> Duration {
+        self.frame_shift * self.frame_subsampling_factor
+    }
+}
+
+/// Options in a Kaldi configuration file.
+#[derive(Debug, Default)]
+struct ConfigFile {
+    /// Path of the file, relative to the model directory.
+    file: PathBuf,
+    options: HashMap<String, String>,
+}
+
+impl ConfigFile {
+    /// Reads the configuration file in `path`, which is inside the model directory `dir`.
+    fn read(dir: &Path, path: &Path) -> Result<Self, Error> {
+        let file = path.strip_prefix(dir).unwrap_or(path).to_path_buf();
+        let contents = fs::read_to_string(path).map_err(|err| Error::InvalidModelConfig {
+            file: file.clone(),
+            reason: err.to_string(),
+        })?;
+
+        let options = contents
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let option = line
+                    .strip_prefix("--")
+                    .ok_or_else(|| Error::InvalidModelConfig {
+                        file: file.clone(),
+                        reason: format!("expected an option starting with --, found {line:?}"),
+                    })?;
+
+                // Options without a value are booleans set to true
+                let (name, value) = option.split_once('=').unwrap_or((option, "true"));
+                Ok((name.trim().to_owned(), value.trim().to_owned()))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self { file, options })
+    }
+
+    /// Parses the value of `option`, returning [`None`] if it is not present.
+    fn parse<T: std::str::FromStr>(&self, option: &str) -> Result<Option<T>, Error> {
+        self.options
+            .get(option)
+            .map(|value| {
+                value.parse().map_err(|_| Error::InvalidModelConfig {
+                    file: self.file.clone(),
+                    reason: format!("invalid value {value:?} for --{option}"),
+                })
+            })
+            .transpose()
+    }
+
+    /// Like [`parse`](Self::parse), but the value must be a finite number greater than zero.
+    fn parse_positive(&self, option: &str) -> Result<Option<f32>, Error> {
+        match self.parse::<f32>(option)? {
+            Some(value) if !(value.is_finite() && value > 0.0) => Err(Error::InvalidModelConfig {
+                file: self.file.clone(),
+                reason: format!("--{option} must be a positive number, found {value}"),
+            }),
+            value => Ok(value),
+        }
+    }
+}
+
+/// Returns the first of `files` that exists in `dir`.
+fn find_file(dir: &Path, files: &[&str]) -> Option<PathBuf> {
+    files
+        .iter()
+        .map(|file| dir.join(file))
+        .find(|path| path.is_file())
+}
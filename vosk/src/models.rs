@@ -1,4 +1,9 @@
-use std::{ffi::CString, ptr::NonNull};
+use crate::{Error, ModelCapabilities, ModelInfo};
+use std::{
+    ffi::CString,
+    path::{Path, PathBuf},
+    ptr::NonNull,
+};
 use vosk_sys::*;
 
 // SAFETY:
@@ -7,20 +12,71 @@ use vosk_sys::*;
 // to pass ther pointer to multiple Recognizers even from immutable references
 // https://github.com/alphacep/vosk-api/blob/a7bc5a22d411e22bebf4df1cc5554b473c7456d4/src/vosk_api.h
 
+/// Files that must be present for Vosk to load a [`Model`].
+///
+/// Each entry lists the accepted locations of a file: the current
+/// layout (`am/`, `conf/`) first, then the legacy flat layout.
+const MODEL_FILES: &[&[&str]] = &[
+    &["am/final.mdl", "final.mdl"],
+    &["conf/mfcc.conf", "mfcc.conf"],
+];
+
+/// Files that must be present for Vosk to load a [`SpeakerModel`].
+const SPEAKER_MODEL_FILES: &[&[&str]] = &[
+    &["mfcc.conf"],
+    &["final.ext.raw"],
+    &["mean.vec"],
+    &["transform.mat"],
+];
+
 /// Model that stores all the data required for recognition.
-pub struct Model(pub(crate) NonNull<VoskModel>);
+pub struct Model(pub(crate) NonNull<VoskModel>, ModelInfo);
 
 impl Model {
-    /// Loads model data from the file and returns the model object, or [`None`]
-    /// if a problem occured.
+    /// Loads model data from the file and returns the model object.
+    ///
+    /// The directory is checked for the files Vosk needs and its configuration
+    /// is read (see [`ModelInfo`]) before loading it, so that a missing file or
+    /// a misconfigured model is reported as such rather than as a generic
+    /// [`Error::NativeInitFailed`].
     ///
     /// * `model_path` - the path to the model directory.
-    #[must_use]
-    pub fn new(model_path: impl Into<String>) -> Option<Self> {
-        let model_path_c = CString::new(model_path.into()).ok()?;
+    pub fn new(model_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let model_path = model_path.as_ref();
+        #[cfg(feature = "dynamic-load")]
+        crate::library::ensure_loaded()?;
+        check_model_dir(model_path, MODEL_FILES)?;
+        let info = ModelInfo::from_dir(model_path)?;
+
+        let model_path_c = path_to_cstring(model_path)?;
         let model_ptr = unsafe { vosk_model_new(model_path_c.as_ptr()) };
 
-        Some(Self(NonNull::new(model_ptr)?))
+        NonNull::new(model_ptr)
+            .map(|model_ptr| Self(model_ptr, info))
+            .ok_or(Error::NativeInitFailed)
+    }
+
+    /// Returns the configuration of the model, read from its directory.
+    #[must_use]
+    pub fn info(&self) -> &ModelInfo {
+        &self.1
+    }
+
+    /// Returns the features supported by the model.
+    #[must_use]
+    pub fn capabilities(&self) -> ModelCapabilities {
+        self.1.capabilities()
+    }
+
+    /// Returns the sample rate of the audio the model was trained with.
+    ///
+    /// Recognizers give the best accuracy when they are fed audio at this rate.
+    /// See [`ResamplingRecognizer`] to convert audio from other rates.
+    ///
+    /// [`ResamplingRecognizer`]: crate::ResamplingRecognizer
+    #[must_use]
+    pub fn sample_rate(&self) -> f32 {
+        self.1.sample_frequency
     }
 
     /// Check if a word can be recognized by the model.
@@ -29,16 +85,13 @@ impl Model {
     ///
     /// Word symbol `0` is for `<epsilon>`.
     #[must_use]
-    pub fn find_word(&mut self, word: &str) -> Option<u16> {
+    pub fn find_word(&self, word: &str) -> Option<u32> {
         let word_c = CString::new(word).ok()?;
 
         let symbol = unsafe { vosk_model_find_word(self.0.as_ptr(), word_c.as_ptr()) };
 
-        if symbol == -1 {
-            None
-        } else {
-            Some(symbol as u16)
-        }
+        // Large models have more than u16::MAX words, and any negative symbol is an error
+        u32::try_from(symbol).ok()
     }
 }
 
@@ -55,16 +108,23 @@ unsafe impl Sync for Model {}
 pub struct SpeakerModel(pub(crate) NonNull<VoskSpkModel>);
 
 impl SpeakerModel {
-    /// Loads speaker model data from the file and returns the model
-    /// object, or [`None`] if a problem occured.
+    /// Loads speaker model data from the file and returns the model object.
+    ///
+    /// Like [`Model::new`], the directory is checked for the required files before loading it.
     ///
     /// * `model_path` - the path to the model in the filesystem.
-    #[must_use]
-    pub fn new(model_path: impl Into<String>) -> Option<Self> {
-        let model_path_c = CString::new(model_path.into()).ok()?;
+    pub fn new(model_path: impl AsRef<Path>) -> Result<Self, Error> {
+        let model_path = model_path.as_ref();
+        #[cfg(feature = "dynamic-load")]
+        crate::library::ensure_loaded()?;
+        check_model_dir(model_path, SPEAKER_MODEL_FILES)?;
+
+        let model_path_c = path_to_cstring(model_path)?;
         let model_ptr = unsafe { vosk_spk_model_new(model_path_c.as_ptr()) };
 
-        Some(Self(NonNull::new(model_ptr)?))
+        NonNull::new(model_ptr)
+            .map(Self)
+            .ok_or(Error::NativeInitFailed)
     }
 }
 
@@ -76,3 +136,69 @@ impl Drop for SpeakerModel {
 
 unsafe impl Send for SpeakerModel {}
 unsafe impl Sync for SpeakerModel {}
+
+/// Model for batch recognition with [`BatchRecognizer`].
+///
+/// Batch recognition requires Vosk to be built with CUDA support, and
+/// [`gpu_init`] must have been called before creating the model.
+///
+/// [`BatchRecognizer`]: crate::BatchRecognizer
+/// [`gpu_init`]: crate::gpu_init
+pub struct BatchModel(pub(crate) NonNull<VoskBatchModel>);
+
+impl BatchModel {
+    /// Creates the batch model object.
+    pub fn new() -> Result<Self, Error> {
+        #[cfg(feature = "dynamic-load")]
+        crate::library::ensure_loaded()?;
+        let model_ptr = unsafe { vosk_batch_model_new() };
+
+        NonNull::new(model_ptr)
+            .map(Self)
+            .ok_or(Error::NativeInitFailed)
+    }
+
+    /// Waits for all the chunks passed to the [`BatchRecognizer`]s using
+    /// this model to be processed.
+    ///
+    /// [`BatchRecognizer`]: crate::BatchRecognizer
+    pub fn wait(&self) {
+        unsafe { vosk_batch_model_wait(self.0.as_ptr()) }
+    }
+}
+
+impl Drop for BatchModel {
+    fn drop(&mut self) {
+        unsafe { vosk_batch_model_free(self.0.as_ptr()) }
+    }
+}
+
+// SAFETY: Vosk synchronizes access to the batch model internally,
+// as it is meant to be fed from many recognizers at once
+unsafe impl Send for BatchModel {}
+unsafe impl Sync for BatchModel {}
+
+/// Converts `path` into a string that can be passed to Vosk.
+pub(crate) fn path_to_cstring(path: &Path) -> Result<CString, Error> {
+    path.to_str()
+        .and_then(|path| CString::new(path).ok())
+        .ok_or_else(|| Error::InvalidPath(path.to_path_buf()))
+}
+
+/// Checks that `dir` is a directory containing at least one of the
+/// alternatives of each entry in `files`.
+fn check_model_dir(dir: &Path, files: &[&[&str]]) -> Result<(), Error> {
+    if !dir.is_dir() {
+        return Err(Error::PathNotFound(dir.to_path_buf()));
+    }
+
+    for alternatives in files {
+        if !alternatives.iter().any(|file| dir.join(file).is_file()) {
+            return Err(Error::MissingModelFile {
+                file: PathBuf::from(alternatives[0]),
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use super::{check_sample_rate, nlsml::parse_nlsml, parse_result};
+use crate::{BatchModel, CompleteResult, Error, NlsmlResult};
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_int},
+    ptr::NonNull,
+};
+use vosk_sys::*;
+
+/// Recognizer that processes audio in batches on the GPU.
+///
+/// Unlike [`Recognizer`], audio is processed asynchronously, so results are queued
+/// and have to be retrieved with [`results`] once they are available.
+///
+/// [`Recognizer`]: crate::Recognizer
+/// [`results`]: Self::results
+pub struct BatchRecognizer(NonNull<VoskBatchRecognizer>);
+
+impl BatchRecognizer {
+    /// Creates the batch recognizer object.
+    ///
+    /// * `model` - [`BatchModel`] that will process the audio. It can be shared across recognizers.
+    ///
+    /// * `sample_rate` - The sample rate of the audio you going to feed into the recognizer.
+    ///   Make sure this rate matches the audio content, it is a common issue causing accuracy problems.
+    pub fn new(model: &BatchModel, sample_rate: f32) -> Result<Self, Error> {
+        check_sample_rate(sample_rate)?;
+
+        let recognizer_ptr = unsafe { vosk_batch_recognizer_new(model.0.as_ptr(), sample_rate) };
+
+        NonNull::new(recognizer_ptr)
+            .map(Self)
+            .ok_or(Error::NativeInitFailed)
+    }
+
+    /// Accept and queue new chunk of voice data for processing.
+    ///
+    /// * `data` - Audio data in PCM 16-bit mono format.
+    pub fn accept_waveform(&mut self, data: &[i16]) {
+        // The batch API only takes bytes, which are read back as native endian 16-bit samples
+        unsafe {
+            vosk_batch_recognizer_accept_waveform(
+                self.0.as_ptr(),
+                data.as_ptr() as *const c_char,
+                std::mem::size_of_val(data) as c_int,
+            )
+        }
+    }
+
+    /// Enables or disables [NLSML](https://www.w3.org/TR/nl-spec/) output.
+    ///
+    /// While enabled, results have to be retrieved with [`nlsml_results`].
+    ///
+    /// [`nlsml_results`]: Self::nlsml_results
+    pub fn set_nlsml(&mut self, enable: bool) {
+        unsafe { vosk_batch_recognizer_set_nlsml(self.0.as_ptr(), i32::from(enable)) }
+    }
+
+    /// Closes the stream, signaling that no more audio will be passed to the recognizer.
+    pub fn finish_stream(&mut self) {
+        unsafe { vosk_batch_recognizer_finish_stream(self.0.as_ptr()) }
+    }
+
+    /// Returns the amount of chunks that are yet to be processed.
+    #[must_use]
+    pub fn pending_chunks(&mut self) -> usize {
+        unsafe { vosk_batch_recognizer_get_pending_chunks(self.0.as_ptr()) }.max(0) as usize
+    }
+
+    /// Returns an iterator that drains the results that are ready.
+    ///
+    /// Each result is removed from the queue as soon as it is returned, so
+    /// they are owned instead of borrowing from the recognizer.
+    pub fn results(&mut self) -> BatchResults<'_> {
+        BatchResults {
+            recognizer: self,
+            parse: |result| parse_result(result).map(CompleteResult::into_owned),
+        }
+    }
+
+    /// Like [`results`], but for recognizers with NLSML enabled (see [`set_nlsml`]).
+    ///
+    /// [`results`]: Self::results
+    /// [`set_nlsml`]: Self::set_nlsml
+    pub fn nlsml_results(&mut self) -> BatchResults<'_, NlsmlResult> {
+        BatchResults {
+            recognizer: self,
+            parse: parse_nlsml,
+        }
+    }
+}
+
+// SAFETY: BatchRecognizer shares no state, so it is Send
+unsafe impl Send for BatchRecognizer {}
+// SAFETY: All methods that mutate BatchRecognizer require a &mut to it,
+// which ensures exclusive access, so it is Sync
+unsafe impl Sync for BatchRecognizer {}
+
+impl Drop for BatchRecognizer {
+    fn drop(&mut self) {
+        unsafe { vosk_batch_recognizer_free(self.0.as_ptr()) }
+    }
+}
+
+/// Iterator over the results that are ready in a [`BatchRecognizer`].
+///
+/// Returned by [`BatchRecognizer::results`] and [`BatchRecognizer::nlsml_results`].
+pub struct BatchResults<'a, T = CompleteResult<'static>> {
+    recognizer: &'a mut BatchRecognizer,
+    parse: fn(&CStr) -> Result<T, Error>,
+}
+
+impl<T> Iterator for BatchResults<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let recognizer_ptr = self.recognizer.0.as_ptr();
+        let front_ptr = unsafe { vosk_batch_recognizer_front_result(recognizer_ptr) };
+        if front_ptr.is_null() {
+            return None;
+        }
+
+        // Vosk returns an empty string when there are no results left
+        let front = unsafe { CStr::from_ptr(front_ptr) };
+        if front.to_bytes().is_empty() {
+            return None;
+        }
+
+        let result = (self.parse)(front);
+        unsafe { vosk_batch_recognizer_pop(recognizer_ptr) };
+
+        Some(result)
+    }
+}
@@ -1,14 +1,21 @@
-use crate::{Model, SpeakerModel};
+use crate::{ChannelLayout, Error, Grammar, Model, Sample, SpeakerModel, Timeline};
+#[cfg(feature = "vosk-0-3-50")]
+use crate::{EndpointerDelays, EndpointerMode};
 use serde::Deserialize;
 use std::{
     ffi::{CStr, CString},
     os::raw::{c_char, c_int},
     ptr::NonNull,
+    time::{Duration, SystemTime},
 };
 use vosk_sys::*;
 
+pub use batch::*;
+pub use nlsml::*;
 pub use results::*;
 
+mod batch;
+mod nlsml;
 mod results;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,56 +45,80 @@ type ResultFn = unsafe extern "C" fn(*mut VoskRecognizer) -> *const c_char;
 
 /// The main object which processes data.
 /// Takes audio as input and returns decoded information as words, confidences, times, and other metadata.
-pub struct Recognizer(NonNull<VoskRecognizer>);
+pub struct Recognizer {
+    ptr: NonNull<VoskRecognizer>,
+    sample_rate: f32,
+
+    /// Whether the model can be restricted to a grammar, see [`Recognizer::set_grammar`].
+    #[cfg_attr(not(feature = "vosk-0-3-45"), allow(dead_code))]
+    supports_runtime_grammar: bool,
+
+    /// Buffer reused across calls to [`accept_samples`](Self::accept_samples)
+    /// to hold the converted samples.
+    sample_buffer: Vec<f32>,
+
+    /// Amount of samples fed to the recognizer since it was created.
+    samples_fed: u64,
+
+    /// First byte of a sample split between two calls to
+    /// [`accept_waveform_bytes`](Self::accept_waveform_bytes).
+    pending_byte: Option<u8>,
+
+    /// Samples of an incomplete frame left by [`accept_interleaved`](Self::accept_interleaved).
+    partial_frame: Vec<f32>,
+
+    /// Wall-clock time of the first sample, see [`Recognizer::set_wall_clock_anchor`].
+    anchor: Option<SystemTime>,
+}
 
 impl Recognizer {
-    /// Creates the recognizer object. Returns [`None`] if a problem occured.
+    /// Creates the recognizer object.
     ///
     /// The recognizers process the speech and return text using shared model data.
     ///
     /// * `model` - [`Model`] containing static data for recognizer. Model can be shared
-    /// across recognizers, even running in different threads.
+    ///   across recognizers, even running in different threads.
     ///
     /// * `sample_rate` - The sample rate of the audio you going to feed into the recognizer.
-    /// Make sure this rate matches the audio content, it is a common issue causing accuracy problems.
+    ///   Make sure this rate matches the audio content, it is a common issue causing accuracy problems.
     ///
     /// [`Model`]: crate::Model
-    #[must_use]
-    pub fn new(model: &Model, sample_rate: f32) -> Option<Self> {
+    pub fn new(model: &Model, sample_rate: f32) -> Result<Self, Error> {
+        check_sample_rate(sample_rate)?;
+
         let recognizer_ptr = unsafe { vosk_recognizer_new(model.0.as_ptr(), sample_rate) };
-        Some(Self(NonNull::new(recognizer_ptr)?))
+        Self::from_ptr(recognizer_ptr, model, sample_rate)
     }
 
-    /// Creates the recognizer object with speaker recognition. Returns [`None`] if a problem occured
+    /// Creates the recognizer object with speaker recognition.
     ///
     /// With the speaker recognition mode the recognizer not just recognize
     /// text but also return speaker vectors one can use for speaker identification
     ///
     /// * `model` - [`Model`] containing the data for recognizer. Model can be
-    /// shared across recognizers, even running in different threads.
+    ///   shared across recognizers, even running in different threads.
     ///
     /// * `sample_rate` - The sample rate of the audio you going to feed into the recognizer.
-    /// Make sure this rate matches the audio content, it is a common
-    /// issue causing accuracy problems.
+    ///   Make sure this rate matches the audio content, it is a common
+    ///   issue causing accuracy problems.
     ///
     /// * `spk_model` - Speaker model for speaker identification.
     ///
     /// [`Model`]: crate::Model
-    #[must_use]
     pub fn new_with_speaker(
         model: &Model,
         sample_rate: f32,
         speaker_model: &SpeakerModel,
-    ) -> Option<Self> {
+    ) -> Result<Self, Error> {
+        check_sample_rate(sample_rate)?;
+
         let recognizer_ptr = unsafe {
             vosk_recognizer_new_spk(model.0.as_ptr(), sample_rate, speaker_model.0.as_ptr())
         };
-
-        Some(Self(NonNull::new(recognizer_ptr)?))
+        Self::from_ptr(recognizer_ptr, model, sample_rate)
     }
 
     /// Creates the recognizer object with that only recognizes certain words.
-    /// Returns [`None`] if a problem occured.
     ///
     /// Sometimes when you want to improve recognition accuracy and when you don't need
     /// to recognize large vocabulary you can specify a list of phrases to recognize. This
@@ -95,50 +126,147 @@ impl Recognizer {
     /// something different.
     ///
     /// Only recognizers with lookahead models support this type of quick configuration.
-    /// Precompiled HCLG graph models are not supported.
+    /// Precompiled HCLG graph models are not supported, and [`Error::GrammarNotSupported`]
+    /// is returned for them (see [`Model::capabilities`]).
     ///
     /// * `model` - [`Model`] containing the data for recognizer. Model can be shared
-    /// across recognizers, even running in different threads.
+    ///   across recognizers, even running in different threads.
     ///
     /// * `sample_rate` - The sample rate of the audio you going to feed into the recognizer.
-    /// Make sure this rate matches the audio content, it is a common issue causing accuracy problems.
+    ///   Make sure this rate matches the audio content, it is a common issue causing accuracy problems.
     ///
-    /// * `grammar` - The list of phrases to recognize.
+    /// * `grammar` - The list of phrases to recognize. See [`Grammar::validate`] to check
+    ///   that the model can recognize all of its words.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// # use vosk::{Model, Recognizer};
+    /// # use vosk::{Grammar, Model, Recognizer};
     /// #
     /// let model = Model::new("/path/to/model").expect("Could not create a model");
     /// let recognizer = Recognizer::new_with_grammar(
     ///     &model,
-    ///     16000.0,
-    ///     &["one two three four five", "[unk]"],
+    ///     model.sample_rate(),
+    ///     &Grammar::from_phrases(["one two three four five", "[unk]"]),
     /// )
     /// .expect("Could not create a recognizer");
     /// ```
     ///
     /// [`Model`]: crate::Model
-    #[must_use]
+    /// [`Grammar::validate`]: crate::Grammar::validate
     pub fn new_with_grammar(
         model: &Model,
         sample_rate: f32,
-        grammar: &[impl AsRef<str>],
-    ) -> Option<Self> {
-        let grammar_c = CString::new(format!(
-            "[{}]",
-            grammar
-                .iter()
-                .map(|phrase| format!("\"{}\"", phrase.as_ref()))
-                .collect::<Vec<_>>()
-                .join(", ")
-        ))
-        .ok()?;
+        grammar: &Grammar,
+    ) -> Result<Self, Error> {
+        check_sample_rate(sample_rate)?;
+        if !model.capabilities().supports_runtime_grammar {
+            return Err(Error::GrammarNotSupported);
+        }
+
+        let grammar_c = CString::new(grammar.to_json()).map_err(|_| Error::InvalidGrammar)?;
         let recognizer_ptr =
             unsafe { vosk_recognizer_new_grm(model.0.as_ptr(), sample_rate, grammar_c.as_ptr()) };
 
-        Some(Self(NonNull::new(recognizer_ptr)?))
+        Self::from_ptr(recognizer_ptr, model, sample_rate)
+    }
+
+    /// Wraps a pointer returned by one of the `vosk_recognizer_new*` functions.
+    fn from_ptr(
+        recognizer_ptr: *mut VoskRecognizer,
+        model: &Model,
+        sample_rate: f32,
+    ) -> Result<Self, Error> {
+        let ptr = NonNull::new(recognizer_ptr).ok_or(Error::NativeInitFailed)?;
+
+        Ok(Self {
+            ptr,
+            sample_rate,
+            supports_runtime_grammar: model.capabilities().supports_runtime_grammar,
+            sample_buffer: Vec::new(),
+            samples_fed: 0,
+            pending_byte: None,
+            partial_frame: Vec::new(),
+            anchor: None,
+        })
+    }
+
+    /// Restricts the recognizer to a different list of phrases, without creating a new one.
+    ///
+    /// Useful to switch between command sets, like the menus of a dialog. The utterance
+    /// that is being recognized is discarded, so call [`final_result`] before if it is needed.
+    ///
+    /// Like [`new_with_grammar`], it returns [`Error::GrammarNotSupported`]
+    /// for models with a precompiled static graph.
+    ///
+    /// Needs Vosk 0.3.45 or newer and the `vosk-0-3-45` feature.
+    ///
+    /// [`final_result`]: Self::final_result
+    /// [`new_with_grammar`]: Self::new_with_grammar
+    #[cfg(feature = "vosk-0-3-45")]
+    pub fn set_grammar(&mut self, grammar: &Grammar) -> Result<(), Error> {
+        if !self.supports_runtime_grammar {
+            return Err(Error::GrammarNotSupported);
+        }
+
+        let grammar_c = CString::new(grammar.to_json()).map_err(|_| Error::InvalidGrammar)?;
+        unsafe { vosk_recognizer_set_grm(self.ptr.as_ptr(), grammar_c.as_ptr()) };
+
+        Ok(())
+    }
+
+    /// Removes the restriction set by [`new_with_grammar`] or [`set_grammar`],
+    /// so that the recognizer can recognize the whole vocabulary of the model again.
+    ///
+    /// Like [`set_grammar`], it discards the utterance that is being recognized.
+    /// It does nothing for models with a precompiled static graph, which cannot be restricted.
+    ///
+    /// Needs Vosk 0.3.45 or newer and the `vosk-0-3-45` feature.
+    ///
+    /// [`new_with_grammar`]: Self::new_with_grammar
+    /// [`set_grammar`]: Self::set_grammar
+    #[cfg(feature = "vosk-0-3-45")]
+    pub fn clear_grammar(&mut self) {
+        if self.supports_runtime_grammar {
+            // An empty list goes back to the model's own graph
+            unsafe { vosk_recognizer_set_grm(self.ptr.as_ptr(), c"[]".as_ptr()) };
+        }
+    }
+
+    /// Changes how long Vosk waits before ending an utterance, with one of its presets.
+    ///
+    /// Needs Vosk 0.3.50 or newer and the `vosk-0-3-50` feature. See [`EndpointingRecognizer`]
+    /// for an alternative that works with older versions.
+    ///
+    /// [`EndpointingRecognizer`]: crate::EndpointingRecognizer
+    #[cfg(feature = "vosk-0-3-50")]
+    pub fn set_endpointer_mode(&mut self, mode: EndpointerMode) {
+        let mode = match mode {
+            EndpointerMode::Default => VoskEndpointerMode_VOSK_EP_ANSWER_DEFAULT,
+            EndpointerMode::Short => VoskEndpointerMode_VOSK_EP_ANSWER_SHORT,
+            EndpointerMode::Long => VoskEndpointerMode_VOSK_EP_ANSWER_LONG,
+            EndpointerMode::VeryLong => VoskEndpointerMode_VOSK_EP_ANSWER_VERY_LONG,
+        };
+
+        unsafe { vosk_recognizer_set_endpointer_mode(self.ptr.as_ptr(), mode) }
+    }
+
+    /// Changes how long Vosk waits before ending an utterance.
+    ///
+    /// Needs Vosk 0.3.50 or newer and the `vosk-0-3-50` feature. See [`EndpointingRecognizer`]
+    /// for an alternative that works with older versions.
+    ///
+    /// [`EndpointingRecognizer`]: crate::EndpointingRecognizer
+    #[cfg(feature = "vosk-0-3-50")]
+    pub fn set_endpointer_delays(&mut self, delays: EndpointerDelays) {
+        unsafe {
+            vosk_recognizer_set_endpointer_delays(
+                self.ptr.as_ptr(),
+                delays.start_max.as_secs_f32(),
+                delays.end.as_secs_f32(),
+                delays.max.as_secs_f32(),
+            );
+        }
     }
 
     /// Adds speaker model to already initialized recognizer
@@ -146,7 +274,7 @@ impl Recognizer {
     /// Can add speaker recognition model to already created recognizer. Helps to initialize
     /// speaker recognition for grammar-based recognizer.
     pub fn set_speaker_model(&mut self, speaker_model: &SpeakerModel) {
-        unsafe { vosk_recognizer_set_spk_model(self.0.as_ptr(), speaker_model.0.as_ptr()) }
+        unsafe { vosk_recognizer_set_spk_model(self.ptr.as_ptr(), speaker_model.0.as_ptr()) }
     }
 
     /// Configures recognizer to output n-best results in [`result`] and [`final_result`]
@@ -161,7 +289,9 @@ impl Recognizer {
     /// [`CompleteResult::Single`]: crate::CompleteResult::Single
     /// [`CompleteResult::Multiple`]: crate::CompleteResult::Multiple
     pub fn set_max_alternatives(&mut self, max_alternatives: u16) {
-        unsafe { vosk_recognizer_set_max_alternatives(self.0.as_ptr(), max_alternatives as c_int) }
+        unsafe {
+            vosk_recognizer_set_max_alternatives(self.ptr.as_ptr(), max_alternatives as c_int)
+        }
     }
 
     /// Enables or disables words with metadata in the output, represented as:
@@ -175,7 +305,7 @@ impl Recognizer {
     /// [`CompleteResult::Multiple`]: crate::CompleteResult::Multiple
     /// [`CompleteResult::Single`]: crate::CompleteResult::Single
     pub fn set_words(&mut self, enable: bool) {
-        unsafe { vosk_recognizer_set_words(self.0.as_ptr(), i32::from(enable)) }
+        unsafe { vosk_recognizer_set_words(self.ptr.as_ptr(), i32::from(enable)) }
     }
 
     /// Like [`set_words`], but for [`PartialResult`].
@@ -186,7 +316,21 @@ impl Recognizer {
     /// [`PartialResult`]: crate::PartialResult
     /// [`Word`]: crate::Word
     pub fn set_partial_words(&mut self, enable: bool) {
-        unsafe { vosk_recognizer_set_partial_words(self.0.as_ptr(), i32::from(enable)) }
+        unsafe { vosk_recognizer_set_partial_words(self.ptr.as_ptr(), i32::from(enable)) }
+    }
+
+    /// Enables or disables [NLSML](https://www.w3.org/TR/nl-spec/) output.
+    ///
+    /// While enabled, complete results have to be retrieved with [`nlsml_result`]
+    /// and [`nlsml_final_result`], as [`result`] and [`final_result`] only understand JSON.
+    /// Partial results are not affected.
+    ///
+    /// [`nlsml_result`]: Self::nlsml_result
+    /// [`nlsml_final_result`]: Self::nlsml_final_result
+    /// [`result`]: Self::result
+    /// [`final_result`]: Self::final_result
+    pub fn set_nlsml(&mut self, enable: bool) {
+        unsafe { vosk_recognizer_set_nlsml(self.ptr.as_ptr(), i32::from(enable)) }
     }
 
     /// Accept and process new chunk of voice data.
@@ -196,18 +340,168 @@ impl Recognizer {
     /// Returns a [`DecodingState`], which represents the state of the decodification
     /// after this chunk of data has been processed.
     pub fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
-        // vosk_recognizer_accept_waveform and vosk_recognizer_accept_waveform_f are meant
-        // to be used by languages that do not have an i16 type (those functions also take PCM 16-bit audio,
-        // but represented as an f32 or i8). Those extra functions aren't needed in rust so they
-        // will not be wrapped
+        let decoding_state = unsafe {
+            vosk_recognizer_accept_waveform_s(self.ptr.as_ptr(), data.as_ptr(), data.len() as c_int)
+        };
+        self.samples_fed += data.len() as u64;
+
+        DecodingState::from_c_int(decoding_state)
+    }
+
+    /// Like [`accept_waveform`], but for audio in 32-bit floating point mono format,
+    /// normalized to `-1.0..=1.0`.
+    ///
+    /// [`accept_waveform`]: Self::accept_waveform
+    pub fn accept_waveform_f32(&mut self, data: &[f32]) -> DecodingState {
+        self.accept_samples(data)
+    }
+
+    /// Like [`accept_waveform`], but for audio in PCM 16-bit little-endian mono format,
+    /// as raw bytes.
+    ///
+    /// `data` does not need to end at a sample boundary, like the chunks read from a
+    /// socket. If it has an odd length, the last byte is kept and joined with the first
+    /// byte of the next call, so that only whole samples are fed to the recognizer.
+    ///
+    /// [`accept_waveform`]: Self::accept_waveform
+    pub fn accept_waveform_bytes(&mut self, mut data: &[u8]) -> DecodingState {
+        let mut first_sample = None;
+        if let (Some(low), Some((&high, rest))) = (self.pending_byte, data.split_first()) {
+            first_sample = Some(i16::from_le_bytes([low, high]));
+            self.pending_byte = None;
+            data = rest;
+        }
+        if data.len() % 2 == 1 {
+            self.pending_byte = data.last().copied();
+            data = &data[..data.len() - 1];
+        }
 
+        // vosk_recognizer_accept_waveform reads the bytes as native endian samples,
+        // so they can only be passed through on little-endian targets
+        if cfg!(target_endian = "little") && first_sample.is_none() {
+            let decoding_state = unsafe {
+                vosk_recognizer_accept_waveform(
+                    self.ptr.as_ptr(),
+                    data.as_ptr() as *const c_char,
+                    data.len() as c_int,
+                )
+            };
+            self.samples_fed += (data.len() / 2) as u64;
+
+            DecodingState::from_c_int(decoding_state)
+        } else {
+            let samples = data
+                .chunks_exact(2)
+                .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]));
+            self.accept_pcm16_iter(
+                first_sample
+                    .into_iter()
+                    .chain(samples)
+                    .map(Sample::to_pcm16),
+            )
+        }
+    }
+
+    /// Like [`accept_waveform`], but for mono audio with any type of [`Sample`].
+    ///
+    /// The samples are converted into a buffer owned by the recognizer, which is reused
+    /// across calls, so no allocations are needed once it is large enough.
+    ///
+    /// [`accept_waveform`]: Self::accept_waveform
+    pub fn accept_samples<S: Sample>(&mut self, data: &[S]) -> DecodingState {
+        self.accept_pcm16_iter(data.iter().map(|sample| sample.to_pcm16()))
+    }
+
+    /// Like [`accept_samples`], but for interleaved multichannel audio,
+    /// which is turned into mono as specified by `layout`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout` has no channels or selects a channel that does not exist.
+    ///
+    /// [`accept_samples`]: Self::accept_samples
+    pub fn accept_interleaved<S: Sample>(
+        &mut self,
+        data: &[S],
+        layout: ChannelLayout,
+    ) -> DecodingState {
+        let mut partial_frame = std::mem::take(&mut self.partial_frame);
+        let decoding_state = self.accept_pcm16_iter(layout.to_mono(&mut partial_frame, data));
+        self.partial_frame = partial_frame;
+
+        decoding_state
+    }
+
+    /// Collects `samples` (see [`Sample::to_pcm16`]) into the sample buffer and feeds
+    /// it to the recognizer.
+    pub(crate) fn accept_pcm16_iter(
+        &mut self,
+        samples: impl Iterator<Item = f32>,
+    ) -> DecodingState {
+        // The samples have to be passed to Vosk in a single call. If a chunk is finalized
+        // in the middle of the data, the next call would clean up the utterance before
+        // its result can be retrieved
+        let mut sample_buffer = std::mem::take(&mut self.sample_buffer);
+        sample_buffer.clear();
+        sample_buffer.extend(samples);
+
+        let decoding_state = self.accept_pcm16(&sample_buffer);
+        self.sample_buffer = sample_buffer;
+
+        decoding_state
+    }
+
+    /// Feeds samples that are already in the range of an [`i16`] (see [`Sample::to_pcm16`])
+    /// to the recognizer.
+    pub(crate) fn accept_pcm16(&mut self, data: &[f32]) -> DecodingState {
         let decoding_state = unsafe {
-            vosk_recognizer_accept_waveform_s(self.0.as_ptr(), data.as_ptr(), data.len() as i32)
+            vosk_recognizer_accept_waveform_f(self.ptr.as_ptr(), data.as_ptr(), data.len() as c_int)
         };
+        self.samples_fed += data.len() as u64;
 
         DecodingState::from_c_int(decoding_state)
     }
 
+    /// Returns the sample rate of the audio the recognizer expects, as passed to its constructor.
+    #[must_use]
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Returns the length of all the audio fed to the recognizer since it was created,
+    /// which is not restarted by [`reset`](Self::reset), like the times reported by Vosk.
+    #[must_use]
+    pub fn position(&self) -> Duration {
+        self.duration_of(self.samples_fed)
+    }
+
+    /// Returns the [`Timeline`] that maps the times in the results
+    /// to offsets in the stream and wall-clock times.
+    ///
+    /// Vosk already counts the times from the first sample fed to the recognizer,
+    /// so its origin is zero, and it only adds the [wall-clock anchor](Self::set_wall_clock_anchor).
+    #[must_use]
+    pub fn timeline(&self) -> Timeline {
+        Timeline {
+            origin: Duration::ZERO,
+            anchor: self.anchor,
+        }
+    }
+
+    /// Sets the wall-clock time of the first sample fed to the recognizer, so that the
+    /// [`Timeline`] also maps the times in the results to wall-clock times.
+    ///
+    /// For live audio, this is usually the time the capture started. If some audio was
+    /// already fed, use [`position`](Self::position) to compute when it started.
+    pub fn set_wall_clock_anchor(&mut self, anchor: SystemTime) {
+        self.anchor = Some(anchor);
+    }
+
+    /// Returns the duration of `samples` at the sample rate of the recognizer.
+    fn duration_of(&self, samples: u64) -> Duration {
+        Duration::from_secs_f64(samples as f64 / f64::from(self.sample_rate))
+    }
+
     /// Returns speech recognition result, waiting for silence (see [`DecodingState::Finalized`]) to give a result.
     ///
     /// The returned value will be a [`CompleteResult::Single`]
@@ -216,12 +510,26 @@ impl Recognizer {
     ///
     /// If words are enabled (see [`set_words`]), it also returns metadata abut the words.
     ///
+    /// # Panics
+    ///
+    /// Panics if the result returned by Vosk cannot be parsed. See [`try_result`]
+    /// for a non-panicking version.
+    ///
     /// [`set_max_alternatives`]: Self::set_max_alternatives
     /// [`set_words`]: Self::set_words
+    /// [`try_result`]: Self::try_result
     /// [`CompleteResult::Multiple`]: crate::CompleteResult::Multiple
     /// [`CompleteResult::Single`]: crate::CompleteResult::Single
     #[must_use]
-    pub fn result(&mut self) -> CompleteResult {
+    pub fn result(&mut self) -> CompleteResult<'_> {
+        self.try_result().unwrap()
+    }
+
+    /// Like [`result`], but returns an [`Error`] containing the raw result
+    /// if it cannot be parsed instead of panicking.
+    ///
+    /// [`result`]: Self::result
+    pub fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
         self.result_with_function(vosk_recognizer_result)
     }
 
@@ -230,42 +538,110 @@ impl Recognizer {
     ///
     /// If words are enabled (see [`set_partial_words`]), it also returns metadata abut the words.
     ///
+    /// # Panics
+    ///
+    /// Panics if the result returned by Vosk cannot be parsed. See [`try_partial_result`]
+    /// for a non-panicking version.
+    ///
     /// [`set_partial_words`]: Self::set_partial_words
+    /// [`try_partial_result`]: Self::try_partial_result
     #[must_use]
-    pub fn partial_result(&mut self) -> PartialResult {
+    pub fn partial_result(&mut self) -> PartialResult<'_> {
+        self.try_partial_result().unwrap()
+    }
+
+    /// Like [`partial_result`], but returns an [`Error`] containing the raw result
+    /// if it cannot be parsed instead of panicking.
+    ///
+    /// [`partial_result`]: Self::partial_result
+    pub fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
         self.result_with_function(vosk_recognizer_partial_result)
     }
 
     /// Returns speech recognition result. Like [`result`] but it does not
     /// wait for silence and it flushes the data so everything is processed
     ///
+    /// # Panics
+    ///
+    /// Panics if the result returned by Vosk cannot be parsed. See [`try_final_result`]
+    /// for a non-panicking version.
+    ///
     /// [`result`]: Self::result
+    /// [`try_final_result`]: Self::try_final_result
     #[must_use]
-    pub fn final_result(&mut self) -> CompleteResult {
+    pub fn final_result(&mut self) -> CompleteResult<'_> {
+        self.try_final_result().unwrap()
+    }
+
+    /// Like [`final_result`], but returns an [`Error`] containing the raw result
+    /// if it cannot be parsed instead of panicking.
+    ///
+    /// [`final_result`]: Self::final_result
+    pub fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
         self.result_with_function(vosk_recognizer_final_result)
     }
 
+    /// Like [`result`], but for recognizers with NLSML enabled (see [`set_nlsml`]).
+    ///
+    /// [`result`]: Self::result
+    /// [`set_nlsml`]: Self::set_nlsml
+    pub fn nlsml_result(&mut self) -> Result<NlsmlResult, Error> {
+        parse_nlsml(self.raw_result(vosk_recognizer_result))
+    }
+
+    /// Like [`final_result`], but for recognizers with NLSML enabled (see [`set_nlsml`]).
+    ///
+    /// [`final_result`]: Self::final_result
+    /// [`set_nlsml`]: Self::set_nlsml
+    pub fn nlsml_final_result(&mut self) -> Result<NlsmlResult, Error> {
+        parse_nlsml(self.raw_result(vosk_recognizer_final_result))
+    }
+
     /// Generic function to retrieve a given type of result from the recognizer.
-    fn result_with_function<'de, T: Deserialize<'de>>(&mut self, function: ResultFn) -> T {
-        // Panics in the result functions will never be the caller's fault, but rather some
-        // edge case that was not thought of, so it does not make sense to return a Result.
+    fn result_with_function<'de, T: Deserialize<'de>>(
+        &'de mut self,
+        function: ResultFn,
+    ) -> Result<T, Error> {
+        parse_result(self.raw_result(function))
+    }
 
-        serde_json::from_str(
-            unsafe { CStr::from_ptr(function(self.0.as_ptr())) }
-                .to_str()
-                .unwrap(),
-        )
-        .unwrap()
+    /// Returns the unparsed result returned by `function`.
+    fn raw_result(&mut self, function: ResultFn) -> &CStr {
+        unsafe { CStr::from_ptr(function(self.ptr.as_ptr())) }
     }
 
     /// Resets current results and data so the recognition can continue from scratch
+    ///
+    /// The times in the results keep counting from the first sample fed to the
+    /// recognizer, they do not restart from zero (see [`position`](Self::position)).
     pub fn reset(&mut self) {
         unsafe {
-            vosk_recognizer_reset(self.0.as_ptr());
+            vosk_recognizer_reset(self.ptr.as_ptr());
         }
     }
 }
 
+/// Parses a JSON result returned by Vosk.
+pub(crate) fn parse_result<'de, T: Deserialize<'de>>(result: &'de CStr) -> Result<T, Error> {
+    let json = result.to_str().map_err(|_| Error::InvalidResultUtf8 {
+        json: result.to_string_lossy().into_owned(),
+    })?;
+
+    serde_json::from_str(json).map_err(|source| Error::InvalidResultJson {
+        json: json.to_owned(),
+        source,
+    })
+}
+
+/// Checks that `sample_rate` can be passed to Vosk.
+pub(crate) fn check_sample_rate(sample_rate: f32) -> Result<(), Error> {
+    if sample_rate.is_finite() && sample_rate > 0.0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidSampleRate(sample_rate))
+    }
+}
+
 // SAFETY: Recognizer shares no state, so it is Send
 unsafe impl Send for Recognizer {}
 // SAFETY: All methods that mutate Recognizer require a &mut to it,
@@ -274,6 +650,6 @@ unsafe impl Sync for Recognizer {}
 
 impl Drop for Recognizer {
     fn drop(&mut self) {
-        unsafe { vosk_recognizer_free(self.0.as_ptr()) }
+        unsafe { vosk_recognizer_free(self.ptr.as_ptr()) }
     }
 }
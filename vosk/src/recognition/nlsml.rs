@@ -0,0 +1,229 @@
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::{ffi::CStr, str::FromStr};
+
+/// Result returned by [`Recognizer::nlsml_result`] and [`Recognizer::nlsml_final_result`],
+/// parsed from the [NLSML](https://www.w3.org/TR/nl-spec/) document returned by Vosk.
+///
+/// It can also be parsed from any NLSML document with [`str::parse`].
+///
+/// [`Recognizer::nlsml_result`]: crate::Recognizer::nlsml_result
+/// [`Recognizer::nlsml_final_result`]: crate::Recognizer::nlsml_final_result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NlsmlResult {
+    /// Grammar that was used to produce the result.
+    pub grammar: Option<String>,
+
+    /// All the possible interpretations of the input, ordered from most to less likely.
+    ///
+    /// There will be more than one if [`Recognizer::set_max_alternatives`]
+    /// is passed a non-zero value.
+    ///
+    /// [`Recognizer::set_max_alternatives`]: crate::Recognizer::set_max_alternatives
+    pub interpretations: Vec<NlsmlInterpretation>,
+}
+
+/// A single interpretation in a [`NlsmlResult`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NlsmlInterpretation {
+    /// Grammar that was matched by this interpretation.
+    pub grammar: Option<String>,
+
+    /// Confidence of the recognizer that this is the correct interpretation.
+    pub confidence: Option<f32>,
+
+    /// Semantic content of the interpretation. For Vosk, this is the transcript text.
+    pub instance: String,
+
+    /// The input that was interpreted, or [`None`] if there was no input (`<noinput/>`).
+    pub input: Option<NlsmlInput>,
+}
+
+/// Input of a [`NlsmlInterpretation`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NlsmlInput {
+    /// Modality of the input, such as `speech`.
+    pub mode: Option<String>,
+
+    /// Text of the input.
+    pub text: String,
+}
+
+impl FromStr for NlsmlResult {
+    type Err = Error;
+
+    fn from_str(xml: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| Error::InvalidResultNlsml {
+            xml: xml.to_owned(),
+            reason,
+        };
+
+        let document = roxmltree::Document::parse(xml).map_err(|err| invalid(err.to_string()))?;
+        let result = document.root_element();
+        if result.tag_name().name() != "result" {
+            return Err(invalid(format!(
+                "expected a <result> root element, found <{}>",
+                result.tag_name().name()
+            )));
+        }
+
+        let interpretations = result
+            .children()
+            .filter(|node| node.has_tag_name("interpretation"))
+            .map(|interpretation| {
+                let confidence = interpretation
+                    .attribute("confidence")
+                    .map(|confidence| {
+                        confidence
+                            .parse()
+                            .map_err(|_| invalid(format!("invalid confidence {confidence:?}")))
+                    })
+                    .transpose()?;
+
+                let input = child_element(interpretation, "input")
+                    .filter(|input| child_element(*input, "noinput").is_none())
+                    .map(|input| NlsmlInput {
+                        mode: input.attribute("mode").map(str::to_owned),
+                        text: text_content(input),
+                    });
+
+                Ok(NlsmlInterpretation {
+                    grammar: interpretation.attribute("grammar").map(str::to_owned),
+                    confidence,
+                    instance: child_element(interpretation, "instance")
+                        .map(text_content)
+                        .unwrap_or_default(),
+                    input,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            grammar: result.attribute("grammar").map(str::to_owned),
+            interpretations,
+        })
+    }
+}
+
+/// Parses an NLSML result returned by Vosk.
+pub(crate) fn parse_nlsml(result: &CStr) -> Result<NlsmlResult, Error> {
+    result
+        .to_str()
+        .map_err(|_| Error::InvalidResultNlsml {
+            xml: result.to_string_lossy().into_owned(),
+            reason: "result is not valid UTF-8".to_owned(),
+        })?
+        .parse()
+}
+
+/// Returns the first child element of `node` called `name`.
+fn child_element<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+/// Returns all the text inside of `node`, without surrounding whitespace.
+fn text_content(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter_map(|node| node.text().filter(|_| node.is_text()))
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the reason why `xml` could not be parsed.
+    fn error(xml: &str) -> String {
+        match xml.parse::<NlsmlResult>() {
+            Err(Error::InvalidResultNlsml { reason, .. }) => reason,
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn interpretations_are_parsed() {
+        let result: NlsmlResult = r#"<?xml version="1.0"?>
+            <result grammar="default">
+                <interpretation grammar="default" confidence="0.98">
+                    <input mode="speech">one two three</input>
+                    <instance>one two three</instance>
+                </interpretation>
+                <interpretation confidence="0.5">
+                    <input mode="speech"> one <b>two</b> tree </input>
+                    <instance>one two tree</instance>
+                </interpretation>
+            </result>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(result.grammar.as_deref(), Some("default"));
+        assert_eq!(
+            result.interpretations,
+            [
+                NlsmlInterpretation {
+                    grammar: Some("default".to_owned()),
+                    confidence: Some(0.98),
+                    instance: "one two three".to_owned(),
+                    input: Some(NlsmlInput {
+                        mode: Some("speech".to_owned()),
+                        text: "one two three".to_owned(),
+                    }),
+                },
+                NlsmlInterpretation {
+                    grammar: None,
+                    confidence: Some(0.5),
+                    instance: "one two tree".to_owned(),
+                    input: Some(NlsmlInput {
+                        mode: Some("speech".to_owned()),
+                        text: "one two tree".to_owned(),
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_input_is_none() {
+        let result: NlsmlResult = r#"<result>
+                <interpretation><input><noinput/></input><instance/></interpretation>
+                <interpretation><instance>yes</instance></interpretation>
+            </result>"#
+            .parse()
+            .unwrap();
+
+        assert_eq!(result.grammar, None);
+        let [noinput, no_input_element] = &result.interpretations[..] else {
+            panic!("unexpected interpretations {:?}", result.interpretations);
+        };
+        assert_eq!((&noinput.input, noinput.instance.as_str()), (&None, ""));
+        assert_eq!(no_input_element.input, None);
+        assert_eq!(no_input_element.confidence, None);
+    }
+
+    #[test]
+    fn invalid_results_are_rejected() {
+        // Malformed XML, reported by the parser
+        assert!(!error("<result>").is_empty());
+        assert_eq!(
+            error("<nlsml/>"),
+            "expected a <result> root element, found <nlsml>"
+        );
+        assert_eq!(
+            error(r#"<result><interpretation confidence="high"/></result>"#),
+            r#"invalid confidence "high""#
+        );
+
+        match parse_nlsml(c"<result>\xff</result>") {
+            Err(Error::InvalidResultNlsml { xml, reason }) => {
+                assert_eq!(xml, "<result>\u{fffd}</result>");
+                assert_eq!(reason, "result is not valid UTF-8");
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use crate::{TimedWord, Timeline};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 
 /// A single word in a [`CompleteResultSingle`] and metadata about it.
 ///
@@ -18,7 +20,21 @@ pub struct Word<'a> {
     pub end: f32,
 
     /// The transcribed word.
-    pub word: &'a str,
+    #[serde(borrow)]
+    pub word: Cow<'a, str>,
+}
+
+impl Word<'_> {
+    /// Converts `self` into a [`Word`] that does not borrow from the recognizer.
+    #[must_use]
+    pub fn into_owned(self) -> Word<'static> {
+        Word {
+            conf: self.conf,
+            start: self.start,
+            end: self.end,
+            word: Cow::Owned(self.word.into_owned()),
+        }
+    }
 }
 
 /// A single word in an [`Alternative`] and metadata about it.
@@ -34,7 +50,20 @@ pub struct WordInAlternative<'a> {
     pub end: f32,
 
     /// The transcribed word.
-    pub word: &'a str,
+    #[serde(borrow)]
+    pub word: Cow<'a, str>,
+}
+
+impl WordInAlternative<'_> {
+    /// Converts `self` into a [`WordInAlternative`] that does not borrow from the recognizer.
+    #[must_use]
+    pub fn into_owned(self) -> WordInAlternative<'static> {
+        WordInAlternative {
+            start: self.start,
+            end: self.end,
+            word: Cow::Owned(self.word.into_owned()),
+        }
+    }
 }
 
 /// An alternative transcript in a [`CompleteResultMultiple`].
@@ -49,11 +78,37 @@ pub struct Alternative<'a> {
     ///
     /// [`text`]: Self::text
     /// [`Recognizer::set_words`]: crate::Recognizer::set_words
-    #[serde(default)]
+    #[serde(default, borrow)]
     pub result: Vec<WordInAlternative<'a>>,
 
     /// Full transcript text.
-    pub text: &'a str,
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+}
+
+impl Alternative<'_> {
+    /// Converts `self` into an [`Alternative`] that does not borrow from the recognizer.
+    #[must_use]
+    pub fn into_owned(self) -> Alternative<'static> {
+        Alternative {
+            confidence: self.confidence,
+            result: self
+                .result
+                .into_iter()
+                .map(WordInAlternative::into_owned)
+                .collect(),
+            text: Cow::Owned(self.text.into_owned()),
+        }
+    }
+
+    /// Returns the [`result`](Self::result) words with their times in `timeline`.
+    #[must_use]
+    pub fn timed_words(&self, timeline: &Timeline) -> Vec<TimedWord<'_>> {
+        self.result
+            .iter()
+            .map(|word| timeline.word_in_alternative(word))
+            .collect()
+    }
 }
 
 /// Recognition result if [`Recognizer::set_max_alternatives`]
@@ -69,6 +124,29 @@ pub struct CompleteResultMultiple<'a> {
     pub alternatives: Vec<Alternative<'a>>,
 }
 
+impl CompleteResultMultiple<'static> {
+    /// Deserializes a [`CompleteResultMultiple`] that does not borrow from the input.
+    ///
+    /// See [`CompleteResult::deserialize_owned`].
+    pub fn deserialize_owned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        CompleteResultMultiple::deserialize(deserializer).map(CompleteResultMultiple::into_owned)
+    }
+}
+
+impl CompleteResultMultiple<'_> {
+    /// Converts `self` into a [`CompleteResultMultiple`] that does not borrow from the recognizer.
+    #[must_use]
+    pub fn into_owned(self) -> CompleteResultMultiple<'static> {
+        CompleteResultMultiple {
+            alternatives: self
+                .alternatives
+                .into_iter()
+                .map(Alternative::into_owned)
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 /// Data useful for speaker identification.
 pub struct SpeakerInfo {
@@ -108,11 +186,39 @@ pub struct CompleteResultSingle<'a> {
     ///
     /// [`text`]: Self::text
     /// [`Recognizer::set_words`]: crate::Recognizer::set_words
-    #[serde(default)]
+    #[serde(default, borrow)]
     pub result: Vec<Word<'a>>,
 
     /// Full text of the transcript.
-    pub text: &'a str,
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+}
+
+impl CompleteResultSingle<'static> {
+    /// Deserializes a [`CompleteResultSingle`] that does not borrow from the input.
+    ///
+    /// See [`CompleteResult::deserialize_owned`].
+    pub fn deserialize_owned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        CompleteResultSingle::deserialize(deserializer).map(CompleteResultSingle::into_owned)
+    }
+}
+
+impl CompleteResultSingle<'_> {
+    /// Converts `self` into a [`CompleteResultSingle`] that does not borrow from the recognizer.
+    #[must_use]
+    pub fn into_owned(self) -> CompleteResultSingle<'static> {
+        CompleteResultSingle {
+            speaker_info: self.speaker_info,
+            result: self.result.into_iter().map(Word::into_owned).collect(),
+            text: Cow::Owned(self.text.into_owned()),
+        }
+    }
+
+    /// Returns the [`result`](Self::result) words with their times in `timeline`.
+    #[must_use]
+    pub fn timed_words(&self, timeline: &Timeline) -> Vec<TimedWord<'_>> {
+        self.result.iter().map(|word| timeline.word(word)).collect()
+    }
 }
 
 /// Different results that can be returned from
@@ -135,6 +241,28 @@ pub enum CompleteResult<'a> {
     Multiple(CompleteResultMultiple<'a>),
 }
 
+impl CompleteResult<'static> {
+    /// Deserializes a [`CompleteResult`] that does not borrow from the input.
+    ///
+    /// The results borrow their text from the input when deserialized with [`Deserialize`],
+    /// so they do not implement [`DeserializeOwned`] and cannot be read with functions
+    /// like `serde_json::from_reader`. This can be used instead, or with
+    /// `#[serde(deserialize_with = "CompleteResult::deserialize_owned")]` on a field.
+    ///
+    /// ```
+    /// # use vosk::CompleteResult;
+    /// let json = br#"{"text": "hello world"}"#;
+    /// let mut deserializer = serde_json::Deserializer::from_reader(&json[..]);
+    /// let result = CompleteResult::deserialize_owned(&mut deserializer).unwrap();
+    /// assert_eq!(result.single().unwrap().text, "hello world");
+    /// ```
+    ///
+    /// [`DeserializeOwned`]: serde::de::DeserializeOwned
+    pub fn deserialize_owned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        CompleteResult::deserialize(deserializer).map(CompleteResult::into_owned)
+    }
+}
+
 impl<'a> CompleteResult<'a> {
     /// Returns the inner [`CompleteResultSingle`] if `self` was [`Single`], and [`None`] otherwise.
     ///
@@ -157,6 +285,16 @@ impl<'a> CompleteResult<'a> {
             Self::Multiple(x) => Some(x),
         }
     }
+
+    /// Converts `self` into a [`CompleteResult`] that does not borrow from the recognizer,
+    /// so it can outlive it or be sent to another thread.
+    #[must_use]
+    pub fn into_owned(self) -> CompleteResult<'static> {
+        match self {
+            Self::Single(x) => CompleteResult::Single(x.into_owned()),
+            Self::Multiple(x) => CompleteResult::Multiple(x.into_owned()),
+        }
+    }
 }
 
 /// Result returned by [`Recognizer::partial_result`].
@@ -168,8 +306,8 @@ pub struct PartialResult<'a> {
     // The "partial" JSON key will not be present if partial_result is called when the recognizer isn't running (DecodingState::Running).
     // It makes sense to return an empty string in that case
     /// Full text of the partial transcript.
-    #[serde(default)]
-    pub partial: &'a str,
+    #[serde(default, borrow)]
+    pub partial: Cow<'a, str>,
 
     /// Collection of words present in [`partial`] with metadata about them.
     ///
@@ -177,6 +315,92 @@ pub struct PartialResult<'a> {
     ///
     /// [`partial`]: Self::partial
     /// [`Recognizer::set_partial_words`]: crate::Recognizer::set_partial_words
-    #[serde(default)]
+    #[serde(default, borrow)]
     pub partial_result: Vec<Word<'a>>,
 }
+
+impl PartialResult<'static> {
+    /// Deserializes a [`PartialResult`] that does not borrow from the input.
+    ///
+    /// See [`CompleteResult::deserialize_owned`].
+    pub fn deserialize_owned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PartialResult::deserialize(deserializer).map(PartialResult::into_owned)
+    }
+}
+
+impl PartialResult<'_> {
+    /// Converts `self` into a [`PartialResult`] that does not borrow from the recognizer,
+    /// so it can outlive it or be sent to another thread.
+    #[must_use]
+    pub fn into_owned(self) -> PartialResult<'static> {
+        PartialResult {
+            partial: Cow::Owned(self.partial.into_owned()),
+            partial_result: self
+                .partial_result
+                .into_iter()
+                .map(Word::into_owned)
+                .collect(),
+        }
+    }
+
+    /// Returns the [`partial_result`](Self::partial_result) words with their times in `timeline`.
+    #[must_use]
+    pub fn timed_words(&self, timeline: &Timeline) -> Vec<TimedWord<'_>> {
+        self.partial_result
+            .iter()
+            .map(|word| timeline.word(word))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Deserializer;
+
+    #[test]
+    fn results_round_trip_without_borrowing() {
+        let single = CompleteResult::Single(CompleteResultSingle {
+            speaker_info: Some(SpeakerInfo {
+                vector: vec![0.5, -1.0],
+                frames: 120,
+            }),
+            result: vec![Word {
+                conf: 0.75,
+                start: 0.5,
+                end: 0.875,
+                word: "caf\u{e9}".into(),
+            }],
+            text: "caf\u{e9}".into(),
+        });
+        let multiple = CompleteResult::Multiple(CompleteResultMultiple {
+            alternatives: vec![Alternative {
+                confidence: 240.5,
+                result: vec![WordInAlternative {
+                    start: 0.0,
+                    end: 0.25,
+                    word: "\"quoted\"".into(),
+                }],
+                text: "\"quoted\"".into(),
+            }],
+        });
+
+        for result in [single, multiple] {
+            let json = serde_json::to_vec(&result).unwrap();
+            // Read from an `io::Read`, which cannot be borrowed from
+            let owned =
+                CompleteResult::deserialize_owned(&mut Deserializer::from_reader(&json[..]))
+                    .unwrap();
+            assert_eq!(owned, result);
+        }
+
+        let partial = PartialResult {
+            partial: "hel".into(),
+            partial_result: Vec::new(),
+        };
+        let json = serde_json::to_vec(&partial).unwrap();
+        let owned =
+            PartialResult::deserialize_owned(&mut Deserializer::from_reader(&json[..])).unwrap();
+        assert_eq!(owned, partial);
+    }
+}
@@ -0,0 +1,443 @@
+use crate::{
+    recognition::check_sample_rate, CompleteResult, CompleteResultSingle, DecodingState, Error,
+    PartialResult, SpeechRecognizer,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// Something that happened to a recognizer while it was being recorded,
+/// stored as a line of JSON in a recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    /// A chunk of audio was accepted and the utterance continues.
+    Running {
+        #[serde(borrow)]
+        partial: PartialResult<'a>,
+    },
+
+    /// A chunk of audio was accepted and ended the utterance.
+    Finalized {
+        #[serde(borrow)]
+        result: CompleteResult<'a>,
+    },
+
+    /// A chunk of audio was accepted and decoding failed.
+    Failed,
+
+    /// The final result was retrieved.
+    FinalResult {
+        #[serde(borrow)]
+        result: CompleteResult<'a>,
+    },
+}
+
+impl Event<'_> {
+    fn into_owned(self) -> Event<'static> {
+        match self {
+            Self::Running { partial } => Event::Running {
+                partial: partial.into_owned(),
+            },
+            Self::Finalized { result } => Event::Finalized {
+                result: result.into_owned(),
+            },
+            Self::Failed => Event::Failed,
+            Self::FinalResult { result } => Event::FinalResult {
+                result: result.into_owned(),
+            },
+        }
+    }
+}
+
+fn empty_result() -> CompleteResult<'static> {
+    CompleteResult::Single(CompleteResultSingle {
+        speaker_info: None,
+        result: Vec::new(),
+        text: Cow::Borrowed(""),
+    })
+}
+
+fn empty_partial_result() -> PartialResult<'static> {
+    PartialResult {
+        partial: Cow::Borrowed(""),
+        partial_result: Vec::new(),
+    }
+}
+
+/// A [`SpeechRecognizer`] that plays back the results recorded by a [`RecordingRecognizer`],
+/// for deterministic tests of code that uses a recognizer, without Vosk or a model.
+///
+/// The audio is ignored. Each chunk accepted plays back the next chunk of the recording,
+/// so it should be fed in chunks of the same size as when it was recorded.
+/// After the end of the recording, the decoding keeps running with an empty partial result.
+///
+/// [`final_result`](SpeechRecognizer::final_result) skips the chunks up to the next final result
+/// in the recording, as if the audio had ended there, and returns it. If there is none,
+/// it returns an empty result. Similarly, if the recording has a final result before the next
+/// chunk, chunks are not played back until the final result is retrieved.
+///
+/// The configuration setters do nothing, as the results were recorded with their configuration.
+///
+/// # Format
+///
+/// Recordings are [JSON Lines](https://jsonlines.org/) files with an event in each line, which
+/// contain the results as returned by Vosk. Empty lines are ignored. The events are:
+///
+/// * `{"event": "running", "partial": <partial result>}`, for chunks that did not end an utterance.
+/// * `{"event": "finalized", "result": <result>}`, for chunks that ended an utterance.
+/// * `{"event": "failed"}`, for chunks that could not be decoded.
+/// * `{"event": "final_result", "result": <result>}`, when the final result was retrieved.
+///
+/// # Examples
+///
+/// ```
+/// # use vosk::{DecodingState, ReplayRecognizer, SpeechRecognizer};
+/// #
+/// let recording = r#"
+/// {"event": "running", "partial": {"partial": "hello"}}
+/// {"event": "finalized", "result": {"text": "hello world"}}
+/// "#;
+/// let mut recognizer = ReplayRecognizer::from_reader(recording.as_bytes(), 16000.0)
+///     .expect("Could not read the recording");
+///
+/// assert_eq!(recognizer.accept_waveform(&[0; 1600]), DecodingState::Running);
+/// assert_eq!(recognizer.partial_result().partial, "hello");
+/// assert_eq!(recognizer.accept_waveform(&[0; 1600]), DecodingState::Finalized);
+/// assert_eq!(recognizer.result().single().unwrap().text, "hello world");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayRecognizer {
+    events: VecDeque<Event<'static>>,
+    sample_rate: f32,
+
+    /// Amount of samples accepted, like [`Recognizer::position`](crate::Recognizer::position).
+    samples: u64,
+    partial: PartialResult<'static>,
+    result: CompleteResult<'static>,
+}
+
+impl ReplayRecognizer {
+    /// Reads the recording at `path`.
+    ///
+    /// `sample_rate` is only used to compute the [`position`](SpeechRecognizer::position) from
+    /// the audio accepted and returned by [`sample_rate`](SpeechRecognizer::sample_rate),
+    /// for code that uses them to measure the audio.
+    pub fn open(path: impl AsRef<Path>, sample_rate: f32) -> Result<Self, Error> {
+        let file = File::open(path).map_err(Error::Io)?;
+        Self::from_reader(BufReader::new(file), sample_rate)
+    }
+
+    /// Like [`open`](Self::open), but reads the recording from `reader`.
+    pub fn from_reader(reader: impl BufRead, sample_rate: f32) -> Result<Self, Error> {
+        check_sample_rate(sample_rate)?;
+
+        let mut events = VecDeque::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(Error::Io)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let event: Event =
+                serde_json::from_str(&line).map_err(|err| Error::InvalidRecording {
+                    line: index + 1,
+                    reason: err.to_string(),
+                })?;
+            events.push_back(event.into_owned());
+        }
+
+        Ok(Self {
+            events,
+            sample_rate,
+            samples: 0,
+            partial: empty_partial_result(),
+            result: empty_result(),
+        })
+    }
+
+    /// Returns whether all the chunks and final results in the recording have been played back.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Plays back the next chunk of the recording, after `samples` samples were accepted.
+    fn accept(&mut self, samples: usize) -> DecodingState {
+        self.samples += samples as u64;
+        if matches!(self.events.front(), None | Some(Event::FinalResult { .. })) {
+            return DecodingState::Running;
+        }
+
+        match self.events.pop_front() {
+            Some(Event::Running { partial }) => {
+                self.partial = partial;
+                self.result = empty_result();
+                DecodingState::Running
+            }
+            Some(Event::Finalized { result }) => {
+                self.partial = empty_partial_result();
+                self.result = result;
+                DecodingState::Finalized
+            }
+            _ => DecodingState::Failed,
+        }
+    }
+}
+
+impl SpeechRecognizer for ReplayRecognizer {
+    fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    fn position(&self) -> Duration {
+        Duration::from_secs_f64(self.samples as f64 / f64::from(self.sample_rate))
+    }
+
+    fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
+        self.accept(data.len())
+    }
+
+    fn accept_waveform_f32(&mut self, data: &[f32]) -> DecodingState {
+        self.accept(data.len())
+    }
+
+    fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        Ok(self.result.clone())
+    }
+
+    fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
+        Ok(self.partial.clone())
+    }
+
+    fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.partial = empty_partial_result();
+        self.result = empty_result();
+
+        while let Some(event) = self.events.pop_front() {
+            if let Event::FinalResult { result } = event {
+                return Ok(result);
+            }
+        }
+
+        Ok(empty_result())
+    }
+
+    fn reset(&mut self) {
+        self.partial = empty_partial_result();
+        self.result = empty_result();
+    }
+
+    fn set_max_alternatives(&mut self, _max_alternatives: u16) {}
+
+    fn set_words(&mut self, _enable: bool) {}
+
+    fn set_partial_words(&mut self, _enable: bool) {}
+}
+
+/// Wraps a [`SpeechRecognizer`] to record its results to `writer`,
+/// so that they can be played back with a [`ReplayRecognizer`].
+///
+/// After each chunk of audio, the partial result or the result of the utterance is retrieved
+/// and recorded. The result of an utterance is kept until it is retrieved with
+/// [`result`](SpeechRecognizer::result), so the inner recognizer is only asked for it once.
+///
+/// Errors, both when retrieving the results and when writing them, stop the recording and
+/// are returned by [`finish`](Self::finish). Results that cannot be parsed are reported as
+/// [`Error::InvalidRecording`], as the recording would be missing them.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::fs::File;
+/// # use vosk::{Model, Recognizer, RecordingRecognizer, SpeechRecognizer};
+/// #
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let recognizer =
+///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+/// let file = File::create("recording.jsonl").expect("Could not create the recording");
+/// let mut recognizer = RecordingRecognizer::new(recognizer, file);
+///
+/// let samples = vec![0i16; 16000];
+/// for chunk in samples.chunks(1600) {
+///     recognizer.accept_waveform(chunk);
+/// }
+/// println!("{:?}", recognizer.final_result());
+///
+/// recognizer.finish().expect("Could not write the recording");
+/// ```
+pub struct RecordingRecognizer<R, W: Write> {
+    recognizer: R,
+    output: Output<W>,
+
+    /// Result of the utterance that ended with the last chunk, if it has not been retrieved yet.
+    finalized_result: Option<CompleteResult<'static>>,
+}
+
+impl<R: SpeechRecognizer, W: Write> RecordingRecognizer<R, W> {
+    /// Wraps `recognizer` so that its results are recorded to `writer`.
+    pub fn new(recognizer: R, writer: W) -> Self {
+        Self {
+            recognizer,
+            output: Output {
+                writer,
+                lines: 0,
+                error: None,
+            },
+            finalized_result: None,
+        }
+    }
+
+    /// Returns a reference to the inner recognizer.
+    #[must_use]
+    pub fn recognizer(&self) -> &R {
+        &self.recognizer
+    }
+
+    /// Stops recording, returning the inner recognizer and the writer after flushing it,
+    /// or the first error that happened while recording.
+    pub fn finish(self) -> Result<(R, W), Error> {
+        let Output {
+            mut writer, error, ..
+        } = self.output;
+        if let Some(err) = error {
+            return Err(err);
+        }
+        writer.flush().map_err(Error::Io)?;
+
+        Ok((self.recognizer, writer))
+    }
+
+    /// Records the results after a chunk of audio was accepted.
+    fn record(&mut self, state: DecodingState) -> DecodingState {
+        self.finalized_result = None;
+
+        match state {
+            DecodingState::Running => match self.recognizer.try_partial_result() {
+                Ok(partial) => self.output.write(&Event::Running { partial }),
+                Err(err) => self.output.fail(&err),
+            },
+            DecodingState::Finalized => match self.recognizer.try_result() {
+                Ok(result) => {
+                    let result = result.into_owned();
+                    self.output.write(&Event::Finalized {
+                        result: result.clone(),
+                    });
+                    self.finalized_result = Some(result);
+                }
+                Err(err) => self.output.fail(&err),
+            },
+            DecodingState::Failed => self.output.write(&Event::Failed),
+        }
+
+        state
+    }
+}
+
+/// Where a [`RecordingRecognizer`] writes the events.
+struct Output<W> {
+    writer: W,
+
+    /// Amount of lines written.
+    lines: usize,
+
+    /// First error that happened while recording, which stopped it.
+    error: Option<Error>,
+}
+
+impl<W: Write> Output<W> {
+    /// Writes `event` as a line.
+    fn write(&mut self, event: &Event) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let result = serde_json::to_writer(&mut self.writer, event)
+            .map_err(io::Error::from)
+            .and_then(|()| self.writer.write_all(b"\n"));
+
+        match result {
+            Ok(()) => self.lines += 1,
+            Err(err) => self.error = Some(Error::Io(err)),
+        }
+    }
+
+    /// Stops recording because the results that should have been
+    /// written to the next line could not be retrieved.
+    fn fail(&mut self, err: &Error) {
+        if self.error.is_none() {
+            self.error = Some(Error::InvalidRecording {
+                line: self.lines + 1,
+                reason: err.to_string(),
+            });
+        }
+    }
+}
+
+impl<R: SpeechRecognizer, W: Write> SpeechRecognizer for RecordingRecognizer<R, W> {
+    fn sample_rate(&self) -> f32 {
+        self.recognizer.sample_rate()
+    }
+
+    fn position(&self) -> Duration {
+        self.recognizer.position()
+    }
+
+    fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
+        let state = self.recognizer.accept_waveform(data);
+        self.record(state)
+    }
+
+    fn accept_waveform_f32(&mut self, data: &[f32]) -> DecodingState {
+        let state = self.recognizer.accept_waveform_f32(data);
+        self.record(state)
+    }
+
+    fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        match self.finalized_result.take() {
+            Some(result) => Ok(result),
+            None => self.recognizer.try_result(),
+        }
+    }
+
+    fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
+        self.recognizer.try_partial_result()
+    }
+
+    fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.finalized_result = None;
+        let result = self.recognizer.try_final_result();
+
+        match &result {
+            Ok(result) => self.output.write(&Event::FinalResult {
+                result: result.clone(),
+            }),
+            Err(err) => self.output.fail(err),
+        }
+
+        result
+    }
+
+    fn reset(&mut self) {
+        self.finalized_result = None;
+        self.recognizer.reset();
+    }
+
+    fn set_max_alternatives(&mut self, max_alternatives: u16) {
+        self.recognizer.set_max_alternatives(max_alternatives);
+    }
+
+    fn set_words(&mut self, enable: bool) {
+        self.recognizer.set_words(enable);
+    }
+
+    fn set_partial_words(&mut self, enable: bool) {
+        self.recognizer.set_partial_words(enable);
+    }
+}
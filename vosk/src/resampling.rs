@@ -0,0 +1,469 @@
+use crate::{
+    recognition::check_sample_rate, ChannelLayout, CompleteResult, DecodingState, Error,
+    PartialResult, Recognizer, Sample,
+};
+use std::f64::consts::PI;
+
+/// Amount of points the filter is sampled at between two input samples.
+const PHASES: usize = 512;
+
+/// Quality of a [`Resampler`], trading accuracy for speed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResamplerQuality {
+    /// Short filter. Suitable for low-powered devices.
+    Fast,
+
+    /// Good balance between accuracy and speed (default).
+    #[default]
+    Medium,
+
+    /// Long filter with a sharp cutoff.
+    High,
+}
+
+impl ResamplerQuality {
+    /// Returns the amount of zero crossings of the sinc function on
+    /// each side of the filter, and the cutoff relative to the Nyquist frequency.
+    fn parameters(self) -> (usize, f64) {
+        match self {
+            Self::Fast => (8, 0.90),
+            Self::Medium => (16, 0.94),
+            Self::High => (32, 0.97),
+        }
+    }
+}
+
+/// Streaming windowed-sinc resampler.
+///
+/// The state is preserved across calls to [`process`], so audio can
+/// be resampled in chunks of any size without discontinuities.
+///
+/// Samples are processed as [`f32`]s, in any range.
+///
+/// [`process`]: Self::process
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    input_rate: f64,
+    output_rate: f64,
+
+    /// Amount of input samples used on each side of an output sample.
+    half_len: usize,
+
+    /// One side of the symmetric filter, sampled [`PHASES`] times per input sample.
+    /// Empty if the input and output rates are equal.
+    filter: Vec<f32>,
+
+    /// Input samples that are still needed to compute the next output samples.
+    buffer: Vec<f32>,
+
+    /// Output samples computed since the resampler was reset.
+    outputs: u64,
+
+    /// Input samples removed from the start of `buffer` since the resampler was reset.
+    consumed: u64,
+}
+
+impl Resampler {
+    /// Creates a resampler that converts audio from `input_rate` to `output_rate`.
+    pub fn new(
+        input_rate: f32,
+        output_rate: f32,
+        quality: ResamplerQuality,
+    ) -> Result<Self, Error> {
+        check_sample_rate(input_rate)?;
+        check_sample_rate(output_rate)?;
+
+        let (input_rate, output_rate) = (f64::from(input_rate), f64::from(output_rate));
+        if input_rate == output_rate {
+            return Ok(Self {
+                input_rate,
+                output_rate,
+                half_len: 0,
+                filter: Vec::new(),
+                buffer: Vec::new(),
+                outputs: 0,
+                consumed: 0,
+            });
+        }
+
+        // When downsampling, the cutoff has to be lowered to the output's Nyquist
+        // frequency to avoid aliasing, which makes the filter proportionally longer
+        let (zero_crossings, rolloff) = quality.parameters();
+        let cutoff = rolloff * (output_rate / input_rate).min(1.0);
+        let half_len = (zero_crossings as f64 / cutoff).ceil() as usize;
+
+        let filter = (0..=half_len * PHASES)
+            .map(|i| {
+                let x = i as f64 / PHASES as f64;
+                (cutoff * sinc(cutoff * x) * blackman(x / half_len as f64)) as f32
+            })
+            .collect();
+
+        let mut resampler = Self {
+            input_rate,
+            output_rate,
+            half_len,
+            filter,
+            buffer: Vec::new(),
+            outputs: 0,
+            consumed: 0,
+        };
+        resampler.reset();
+
+        Ok(resampler)
+    }
+
+    /// Resamples `input` and appends the result to `output`.
+    ///
+    /// Some samples are held back until enough input is available to compute
+    /// them, so call [`flush`] at the end of the stream.
+    ///
+    /// [`flush`]: Self::flush
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        if self.filter.is_empty() {
+            output.extend_from_slice(input);
+            return;
+        }
+
+        self.buffer.extend_from_slice(input);
+
+        let mut position = self.position();
+        while (position as usize) + self.half_len < self.buffer.len() {
+            output.push(self.interpolate(position));
+            self.outputs += 1;
+            position = self.position();
+        }
+
+        // Only keep the samples that will be used by the next output sample
+        let consumed = (position as usize + 1).saturating_sub(self.half_len);
+        let consumed = consumed.min(self.buffer.len());
+        self.buffer.drain(..consumed);
+        self.consumed += consumed as u64;
+    }
+
+    /// Appends the samples that were held back by [`process`] to `output`,
+    /// and resets the resampler so it can be used with a new stream.
+    ///
+    /// [`process`]: Self::process
+    pub fn flush(&mut self, output: &mut Vec<f32>) {
+        if !self.filter.is_empty() {
+            // Pad the input with silence so the last samples can be computed
+            let end = self.buffer.len() as f64;
+            self.buffer.resize(self.buffer.len() + self.half_len, 0.0);
+
+            let mut position = self.position();
+            while position < end {
+                output.push(self.interpolate(position));
+                self.outputs += 1;
+                position = self.position();
+            }
+        }
+
+        self.reset();
+    }
+
+    /// Discards all the samples that were held back by [`process`].
+    ///
+    /// [`process`]: Self::process
+    pub fn reset(&mut self) {
+        // Start with silence, so the first output sample is aligned with the first input sample
+        self.buffer.clear();
+        self.buffer.resize(self.half_len, 0.0);
+        self.outputs = 0;
+        self.consumed = 0;
+    }
+
+    /// Returns the position of the next output sample in the buffer.
+    fn position(&self) -> f64 {
+        // Computed from the amount of outputs rather than accumulated, so that rounding
+        // errors do not add up and depend on the size of the chunks. The position is exact
+        // when it falls on an input sample, so no sample is added or lost at the end
+        self.outputs as f64 * self.input_rate / self.output_rate - self.consumed as f64
+            + self.half_len as f64
+    }
+
+    /// Computes the output sample at `position` in the buffer.
+    fn interpolate(&self, position: f64) -> f32 {
+        let center = position as usize;
+        let first = center + 1 - self.half_len;
+        let last = center + self.half_len;
+
+        (first..=last)
+            .map(|i| self.buffer[i] * self.filter_at((i as f64 - position).abs()))
+            .sum()
+    }
+
+    /// Returns the value of the filter `distance` input samples away from its center.
+    fn filter_at(&self, distance: f64) -> f32 {
+        let index = distance * PHASES as f64;
+        let i = index as usize;
+        if i + 1 >= self.filter.len() {
+            return 0.0;
+        }
+
+        let fraction = (index - i as f64) as f32;
+        self.filter[i] + (self.filter[i + 1] - self.filter[i]) * fraction
+    }
+}
+
+/// Normalized sinc function.
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Blackman window, where `x` goes from `-1.0` to `1.0`.
+fn blackman(x: f64) -> f64 {
+    0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos()
+}
+
+/// [`Recognizer`] that accepts audio at any sample rate and
+/// resamples it to the rate the recognizer was created with.
+///
+/// Most models are trained with 16 kHz audio, while audio devices usually record
+/// at 44.1 or 48 kHz. Feeding resampled audio gives better accuracy than
+/// creating the recognizer with the rate of the device.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use vosk::{Model, Recognizer, ResamplerQuality, ResamplingRecognizer};
+/// #
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let recognizer = Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+/// let mut recognizer = ResamplingRecognizer::new(recognizer, 48000.0, ResamplerQuality::Medium)
+///     .expect("Could not create a resampling recognizer");
+///
+/// let samples = vec![0i16; 4800];
+/// recognizer.accept_waveform(&samples);
+/// ```
+pub struct ResamplingRecognizer {
+    recognizer: Recognizer,
+    resampler: Resampler,
+    input_buffer: Vec<f32>,
+    output_buffer: Vec<f32>,
+
+    /// Samples of an incomplete frame left by [`accept_interleaved`](Self::accept_interleaved).
+    partial_frame: Vec<f32>,
+}
+
+impl ResamplingRecognizer {
+    /// Wraps `recognizer` so that it accepts audio at `input_rate`.
+    ///
+    /// * `recognizer` - The recognizer that will process the audio, at the sample rate it was created with.
+    ///
+    /// * `input_rate` - The sample rate of the audio you are going to feed into the recognizer.
+    ///
+    /// * `quality` - Quality of the resampler.
+    pub fn new(
+        recognizer: Recognizer,
+        input_rate: f32,
+        quality: ResamplerQuality,
+    ) -> Result<Self, Error> {
+        let resampler = Resampler::new(input_rate, recognizer.sample_rate(), quality)?;
+
+        Ok(Self {
+            recognizer,
+            resampler,
+            input_buffer: Vec::new(),
+            output_buffer: Vec::new(),
+            partial_frame: Vec::new(),
+        })
+    }
+
+    /// Returns a reference to the inner [`Recognizer`].
+    #[must_use]
+    pub fn recognizer(&self) -> &Recognizer {
+        &self.recognizer
+    }
+
+    /// Returns a mutable reference to the inner [`Recognizer`], which can be used to configure it.
+    ///
+    /// Audio fed directly to it will not be resampled.
+    #[must_use]
+    pub fn recognizer_mut(&mut self) -> &mut Recognizer {
+        &mut self.recognizer
+    }
+
+    /// Returns the inner [`Recognizer`], discarding any audio held back by the resampler.
+    #[must_use]
+    pub fn into_inner(self) -> Recognizer {
+        self.recognizer
+    }
+
+    /// Like [`Recognizer::accept_waveform`], but `data` is at the input rate.
+    pub fn accept_waveform(&mut self, data: &[i16]) -> DecodingState {
+        self.accept_samples(data)
+    }
+
+    /// Like [`Recognizer::accept_samples`], but `data` is at the input rate.
+    pub fn accept_samples<S: Sample>(&mut self, data: &[S]) -> DecodingState {
+        self.input_buffer.clear();
+        self.input_buffer
+            .extend(data.iter().map(|sample| sample.to_pcm16()));
+
+        self.resample_input()
+    }
+
+    /// Like [`Recognizer::accept_interleaved`], but `data` is at the input rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `layout` has no channels or selects a channel that does not exist.
+    pub fn accept_interleaved<S: Sample>(
+        &mut self,
+        data: &[S],
+        layout: ChannelLayout,
+    ) -> DecodingState {
+        self.input_buffer.clear();
+        self.input_buffer
+            .extend(layout.to_mono(&mut self.partial_frame, data));
+
+        self.resample_input()
+    }
+
+    /// Resamples the input buffer and feeds it to the recognizer.
+    fn resample_input(&mut self) -> DecodingState {
+        self.output_buffer.clear();
+        self.resampler
+            .process(&self.input_buffer, &mut self.output_buffer);
+
+        self.recognizer.accept_pcm16(&self.output_buffer)
+    }
+
+    /// See [`Recognizer::result`].
+    #[must_use]
+    pub fn result(&mut self) -> CompleteResult<'_> {
+        self.recognizer.result()
+    }
+
+    /// See [`Recognizer::try_result`].
+    pub fn try_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.recognizer.try_result()
+    }
+
+    /// See [`Recognizer::partial_result`].
+    #[must_use]
+    pub fn partial_result(&mut self) -> PartialResult<'_> {
+        self.recognizer.partial_result()
+    }
+
+    /// See [`Recognizer::try_partial_result`].
+    pub fn try_partial_result(&mut self) -> Result<PartialResult<'_>, Error> {
+        self.recognizer.try_partial_result()
+    }
+
+    /// Like [`Recognizer::final_result`], but it also flushes the audio held back by the resampler.
+    #[must_use]
+    pub fn final_result(&mut self) -> CompleteResult<'_> {
+        self.flush();
+        self.recognizer.final_result()
+    }
+
+    /// Like [`Recognizer::try_final_result`], but it also flushes the audio held back by the resampler.
+    pub fn try_final_result(&mut self) -> Result<CompleteResult<'_>, Error> {
+        self.flush();
+        self.recognizer.try_final_result()
+    }
+
+    /// Like [`Recognizer::reset`], but it also discards the audio held back by the resampler.
+    pub fn reset(&mut self) {
+        self.resampler.reset();
+        self.recognizer.reset();
+    }
+
+    /// Feeds the audio held back by the resampler to the recognizer.
+    fn flush(&mut self) {
+        self.output_buffer.clear();
+        self.resampler.flush(&mut self.output_buffer);
+
+        if !self.output_buffer.is_empty() {
+            self.recognizer.accept_pcm16(&self.output_buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns `seconds` of a sine wave of `frequency` at `rate`.
+    fn tone(frequency: f64, rate: f64, seconds: f64) -> Vec<f32> {
+        (0..(rate * seconds) as usize)
+            .map(|i| (2.0 * PI * frequency * i as f64 / rate).sin() as f32)
+            .collect()
+    }
+
+    /// Resamples `input` to 16 kHz in chunks of `chunk` samples.
+    fn to_16k(input: &[f32], input_rate: f32, chunk: usize) -> Vec<f32> {
+        let mut resampler = Resampler::new(input_rate, 16000.0, ResamplerQuality::Medium).unwrap();
+        let mut output = Vec::new();
+        for chunk in input.chunks(chunk) {
+            resampler.process(chunk, &mut output);
+        }
+        resampler.flush(&mut output);
+        output
+    }
+
+    /// Returns the largest difference between `actual` and `expected`,
+    /// ignoring the edges where the filter reaches past the input.
+    fn max_error(actual: &[f32], expected: &[f32]) -> f32 {
+        assert_eq!(actual.len(), expected.len());
+        let edge = 200;
+        actual[edge..actual.len() - edge]
+            .iter()
+            .zip(&expected[edge..expected.len() - edge])
+            .map(|(actual, expected)| (actual - expected).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn common_rates_are_converted_to_16k() {
+        for rate in [48000.0, 44100.0, 8000.0] {
+            let input = tone(440.0, rate, 1.0);
+            let output = to_16k(&input, rate as f32, 1000);
+
+            // One second of audio stays one second long, and the tone keeps its frequency
+            assert_eq!(output.len(), 16000, "{rate}");
+            let error = max_error(&output, &tone(440.0, 16000.0, 1.0));
+            assert!(error < 0.01, "{rate}: {error}");
+        }
+    }
+
+    #[test]
+    fn chunks_do_not_change_the_output() {
+        let input = tone(1000.0, 44100.0, 0.5);
+        let whole = to_16k(&input, 44100.0, input.len());
+
+        for chunk in [1, 7, 441, 4096] {
+            assert_eq!(to_16k(&input, 44100.0, chunk), whole, "{chunk}");
+        }
+    }
+
+    #[test]
+    fn frequencies_above_the_output_nyquist_are_removed() {
+        // Would alias to 4 kHz without filtering
+        let input = tone(12000.0, 48000.0, 1.0);
+        let output = to_16k(&input, 48000.0, 4800);
+
+        let peak = output[200..output.len() - 200]
+            .iter()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        assert!(peak < 0.01, "{peak}");
+    }
+
+    #[test]
+    fn equal_rates_are_passed_through() {
+        let input = tone(440.0, 16000.0, 0.1);
+        assert_eq!(to_16k(&input, 16000.0, 100), input);
+    }
+
+    #[test]
+    fn invalid_rates_are_rejected() {
+        assert!(Resampler::new(0.0, 16000.0, ResamplerQuality::Fast).is_err());
+        assert!(Resampler::new(48000.0, f32::NAN, ResamplerQuality::Fast).is_err());
+    }
+}
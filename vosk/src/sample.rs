@@ -0,0 +1,60 @@
+/// A type of audio sample that can be fed to a [`Recognizer`] with [`Recognizer::accept_samples`].
+///
+/// Vosk works with 16-bit PCM audio, so every sample is scaled to the range of an [`i16`].
+/// Floating point samples are expected to be normalized to `-1.0..=1.0`.
+///
+/// [`Recognizer`]: crate::Recognizer
+/// [`Recognizer::accept_samples`]: crate::Recognizer::accept_samples
+pub trait Sample: Copy {
+    /// Converts the sample into a 16-bit PCM value, represented as an [`f32`]
+    /// as expected by `vosk_recognizer_accept_waveform_f`.
+    fn to_pcm16(self) -> f32;
+}
+
+impl Sample for i16 {
+    fn to_pcm16(self) -> f32 {
+        f32::from(self)
+    }
+}
+
+impl Sample for u16 {
+    fn to_pcm16(self) -> f32 {
+        (i32::from(self) - 0x8000) as f32
+    }
+}
+
+impl Sample for i8 {
+    fn to_pcm16(self) -> f32 {
+        f32::from(self) * 256.0
+    }
+}
+
+impl Sample for u8 {
+    fn to_pcm16(self) -> f32 {
+        (i32::from(self) - 0x80) as f32 * 256.0
+    }
+}
+
+impl Sample for i32 {
+    fn to_pcm16(self) -> f32 {
+        (f64::from(self) / 65536.0) as f32
+    }
+}
+
+impl Sample for u32 {
+    fn to_pcm16(self) -> f32 {
+        ((f64::from(self) - 2_147_483_648.0) / 65536.0) as f32
+    }
+}
+
+impl Sample for f32 {
+    fn to_pcm16(self) -> f32 {
+        (self * 32768.0).clamp(-32768.0, 32767.0)
+    }
+}
+
+impl Sample for f64 {
+    fn to_pcm16(self) -> f32 {
+        (self * 32768.0).clamp(-32768.0, 32767.0) as f32
+    }
+}
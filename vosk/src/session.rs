@@ -0,0 +1,238 @@
+use crate::{
+    backend::LastPartial, CompleteResult, DecodingState, Error, PartialResult, Recognizer,
+    SpeechRecognizer,
+};
+use std::{ops::Range, time::Duration};
+
+/// An utterance that ended, passed to [`StreamingSession::on_final`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utterance<'a> {
+    /// Result of the utterance.
+    pub result: CompleteResult<'a>,
+
+    /// Offset in the stream of the audio where the utterance started, which
+    /// is where the previous one ended, as Vosk does not report the silence before it.
+    pub start: Duration,
+
+    /// Offset in the stream of the end of the chunk of audio that ended the utterance.
+    pub end: Duration,
+}
+
+type PartialCallback = Box<dyn FnMut(&PartialResult<'_>) + Send>;
+type FinalCallback = Box<dyn FnMut(Utterance<'_>) + Send>;
+type SilenceCallback = Box<dyn FnMut(Range<Duration>) + Send>;
+
+/// Wraps a recognizer to report its results through callbacks while audio is fed to it,
+/// instead of checking the [`DecodingState`] and asking for the results after every chunk.
+///
+/// * [`on_partial`](Self::on_partial) is only called when the text of the partial result changes.
+/// * [`on_final`](Self::on_final) is called when an utterance with some text ends.
+/// * [`on_silence`](Self::on_silence) is called instead when an utterance without text ends,
+///   which is how Vosk ends segments of silence or noise.
+///
+/// Offsets in the stream are the [position](SpeechRecognizer::position) of the recognizer,
+/// so they are counted from the first sample fed to it, like the times of the words.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use vosk::{Model, Recognizer, StreamingSession};
+/// #
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let recognizer =
+///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+///
+/// let mut session = StreamingSession::new(recognizer)
+///     .on_partial(|partial| println!("... {}", partial.partial))
+///     .on_final(|utterance| {
+///         if let Some(result) = utterance.result.single() {
+///             println!("{:?}: {}", utterance.start, result.text);
+///         }
+///     });
+///
+/// let samples = vec![0i16; 16000];
+/// for chunk in samples.chunks(1600) {
+///     session.accept_waveform(chunk).expect("Could not recognize the audio");
+/// }
+/// session.finish().expect("Could not recognize the audio");
+/// ```
+pub struct StreamingSession<R = Recognizer> {
+    recognizer: R,
+    on_partial: Option<PartialCallback>,
+    on_final: Option<FinalCallback>,
+    on_silence: Option<SilenceCallback>,
+
+    /// Position of the recognizer when the current utterance started.
+    utterance_start: Duration,
+    last_partial: LastPartial,
+}
+
+impl<R: SpeechRecognizer> StreamingSession<R> {
+    /// Wraps `recognizer`, without any callbacks.
+    #[must_use]
+    pub fn new(recognizer: R) -> Self {
+        let utterance_start = recognizer.position();
+
+        Self {
+            recognizer,
+            on_partial: None,
+            on_final: None,
+            on_silence: None,
+            utterance_start,
+            last_partial: LastPartial::default(),
+        }
+    }
+
+    /// Sets the function called when the text of the partial result changes.
+    #[must_use]
+    pub fn on_partial(mut self, callback: impl FnMut(&PartialResult<'_>) + Send + 'static) -> Self {
+        self.on_partial = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the function called when an utterance with some text ends.
+    #[must_use]
+    pub fn on_final(mut self, callback: impl FnMut(Utterance<'_>) + Send + 'static) -> Self {
+        self.on_final = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the function called when an utterance without any text ends,
+    /// with the span of the stream it covered.
+    #[must_use]
+    pub fn on_silence(mut self, callback: impl FnMut(Range<Duration>) + Send + 'static) -> Self {
+        self.on_silence = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns a reference to the inner recognizer.
+    #[must_use]
+    pub fn recognizer(&self) -> &R {
+        &self.recognizer
+    }
+
+    /// Returns a mutable reference to the inner recognizer, which can be used to configure it.
+    ///
+    /// Audio fed directly to it counts in the offsets, but its results are not reported.
+    #[must_use]
+    pub fn recognizer_mut(&mut self) -> &mut R {
+        &mut self.recognizer
+    }
+
+    /// Returns the inner recognizer.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.recognizer
+    }
+
+    /// Returns the length of the audio fed to the recognizer (see [`SpeechRecognizer::position`]).
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.recognizer.position()
+    }
+
+    /// Feeds a chunk of audio to the recognizer (see [`Recognizer::accept_waveform`])
+    /// and calls the callbacks for its results.
+    ///
+    /// Returns [`Error::DecodingFailed`] if the chunk could not be decoded, or an error if
+    /// its results could not be parsed. The session can still be used after an error.
+    pub fn accept_waveform(&mut self, data: &[i16]) -> Result<(), Error> {
+        let state = self.recognizer.accept_waveform(data);
+        self.update(state)
+    }
+
+    /// Like [`accept_waveform`](Self::accept_waveform), but for audio in 32-bit
+    /// floating point format (see [`Recognizer::accept_waveform_f32`]).
+    pub fn accept_waveform_f32(&mut self, data: &[f32]) -> Result<(), Error> {
+        let state = self.recognizer.accept_waveform_f32(data);
+        self.update(state)
+    }
+
+    /// Ends the current utterance, if there is one, as if the stream had ended,
+    /// and calls [`on_final`](Self::on_final) or [`on_silence`](Self::on_silence) for it.
+    ///
+    /// The session can keep being used after it, like the recognizer after a
+    /// [`final_result`](Recognizer::final_result).
+    pub fn finish(&mut self) -> Result<(), Error> {
+        self.last_partial.clear();
+        let span = self.end_utterance();
+        // Nothing to report if no audio was fed since the last utterance
+        let has_audio = !span.is_empty();
+
+        let result = self.recognizer.try_final_result()?;
+        if has_audio {
+            report(&mut self.on_final, &mut self.on_silence, result, span);
+        }
+
+        Ok(())
+    }
+
+    /// Discards the current utterance (see [`Recognizer::reset`]) without calling any callbacks.
+    ///
+    /// The audio that was fed is still counted in the offsets of the next utterances.
+    pub fn reset(&mut self) {
+        self.recognizer.reset();
+        self.last_partial.clear();
+        self.utterance_start = self.recognizer.position();
+    }
+
+    /// Calls the callbacks for the results after a chunk was fed with the given `state`.
+    fn update(&mut self, state: DecodingState) -> Result<(), Error> {
+        match state {
+            DecodingState::Running => {
+                let partial = self.recognizer.try_partial_result()?;
+                if self.last_partial.changed(&partial) {
+                    if let Some(on_partial) = &mut self.on_partial {
+                        on_partial(&partial);
+                    }
+                }
+
+                Ok(())
+            }
+            DecodingState::Finalized => {
+                self.last_partial.clear();
+                let span = self.end_utterance();
+
+                let result = self.recognizer.try_result()?;
+                report(&mut self.on_final, &mut self.on_silence, result, span);
+
+                Ok(())
+            }
+            DecodingState::Failed => Err(Error::DecodingFailed),
+        }
+    }
+
+    /// Starts the next utterance at the current position, returning the span of the last one.
+    fn end_utterance(&mut self) -> Range<Duration> {
+        let end = self.recognizer.position();
+        std::mem::replace(&mut self.utterance_start, end)..end
+    }
+}
+
+/// Calls `on_final` with `result` if it has some text, and `on_silence` otherwise.
+fn report(
+    on_final: &mut Option<FinalCallback>,
+    on_silence: &mut Option<SilenceCallback>,
+    result: CompleteResult<'_>,
+    span: Range<Duration>,
+) {
+    let is_empty = match &result {
+        CompleteResult::Single(result) => result.text.is_empty(),
+        CompleteResult::Multiple(result) => result
+            .alternatives
+            .iter()
+            .all(|alternative| alternative.text.is_empty()),
+    };
+
+    if is_empty {
+        if let Some(on_silence) = on_silence {
+            on_silence(span);
+        }
+    } else if let Some(on_final) = on_final {
+        on_final(Utterance {
+            result,
+            start: span.start,
+            end: span.end,
+        });
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    backend::LastPartial, CompleteResult, DecodingState, Error, PartialResult, Recognizer,
+    SpeechRecognizer,
+};
+use futures_core::Stream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc;
+use tokio_util::sync::PollSender;
+
+/// Amount of chunks of audio that can be waiting to be processed before
+/// a [`RecognitionStream`] stops polling the audio stream.
+const QUEUED_CHUNKS: usize = 16;
+
+/// Audio of a [`RecognitionStream`] and the channel it is sent to the thread through.
+type Input<S> = (Pin<Box<S>>, PollSender<Vec<i16>>);
+
+/// Item of a [`RecognitionStream`].
+#[derive(Debug)]
+pub enum RecognitionEvent {
+    /// The partial result of the current utterance changed.
+    Partial(PartialResult<'static>),
+
+    /// An utterance ended, or the audio ended and this is the final result.
+    Final(CompleteResult<'static>),
+
+    /// A chunk of audio could not be decoded, or its result could not be parsed.
+    Error(Error),
+}
+
+/// [`Stream`] of the [`RecognitionEvent`]s for a stream of audio, returned
+/// by [`Recognizer::into_stream`] and [`RecognitionStream::new`].
+///
+/// The recognizer runs on a dedicated thread, so the blocking calls to Vosk never stall the
+/// async runtime. Polling the stream feeds the audio to that thread, which processes each chunk
+/// as [`Recognizer::accept_waveform`] would, and yields the events once they are ready.
+/// If the recognizer falls behind, the audio stream is not polled until it catches up.
+///
+/// Partial results are only yielded when their text changes. When the audio stream ends, the
+/// [final result](Recognizer::final_result) is yielded as a last [`RecognitionEvent::Final`].
+///
+/// The stream does not depend on a specific runtime, although it uses `tokio`'s channels.
+/// Dropping it stops the thread after the chunk that is being processed.
+pub struct RecognitionStream<S> {
+    /// Audio that has not been sent to the thread yet and the channel it is sent through.
+    /// [`None`] once the audio has ended, which closes the channel.
+    input: Option<Input<S>>,
+    events: mpsc::UnboundedReceiver<RecognitionEvent>,
+}
+
+impl<S: Stream<Item = Vec<i16>>> RecognitionStream<S> {
+    /// Returns a stream of the events of `recognizer` for `audio`, which contains chunks
+    /// of audio in the format expected by [`SpeechRecognizer::accept_waveform`].
+    ///
+    /// See [`Recognizer::into_stream`], which is a shorthand for a [`Recognizer`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread cannot be created.
+    pub fn new<R>(recognizer: R, audio: S) -> Self
+    where
+        R: SpeechRecognizer + Send + 'static,
+    {
+        let (chunk_sender, chunk_receiver) = mpsc::channel(QUEUED_CHUNKS);
+        // Events are not bounded, so that the thread never blocks while the audio stream waits
+        // for it. There is at most an event for each chunk, so they are bounded by them anyway
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+
+        std::thread::Builder::new()
+            .name("vosk-recognizer".to_owned())
+            .spawn(move || recognize(recognizer, chunk_receiver, event_sender))
+            .expect("Could not create the recognizer thread");
+
+        Self {
+            input: Some((Box::pin(audio), PollSender::new(chunk_sender))),
+            events: event_receiver,
+        }
+    }
+}
+
+impl<S: Stream<Item = Vec<i16>>> Stream for RecognitionStream<S> {
+    type Item = RecognitionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        // Feed the thread until it is full or the audio stream has nothing else yet
+        while let Some((audio, chunks)) = &mut this.input {
+            let ended = match chunks.poll_reserve(cx) {
+                Poll::Ready(Ok(())) => match audio.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(chunk)) => {
+                        // Cannot fail, a slot has just been reserved
+                        let _ = chunks.send_item(chunk);
+                        false
+                    }
+                    Poll::Ready(None) => true,
+                    Poll::Pending => break,
+                },
+                // The thread stopped, which ends the events too
+                Poll::Ready(Err(_)) => true,
+                Poll::Pending => break,
+            };
+
+            if ended {
+                this.input = None;
+            }
+        }
+
+        this.events.poll_recv(cx)
+    }
+}
+
+impl Recognizer {
+    /// Returns a [`Stream`] of the events of the recognizer for `audio`, which
+    /// contains chunks of audio in the same format as [`accept_waveform`].
+    ///
+    /// The recognizer runs on a dedicated thread, see [`RecognitionStream`].
+    ///
+    /// Needs the `async` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use futures::{stream, StreamExt};
+    /// # use vosk::{Model, RecognitionEvent, Recognizer};
+    /// #
+    /// # async fn run() {
+    /// let model = Model::new("/path/to/model").expect("Could not create a model");
+    /// let recognizer =
+    ///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+    ///
+    /// let audio = stream::iter(vec![vec![0i16; 1600]; 10]);
+    /// let mut events = recognizer.into_stream(audio);
+    /// while let Some(event) = events.next().await {
+    ///     if let RecognitionEvent::Final(result) = event {
+    ///         println!("{result:?}");
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [`accept_waveform`]: Self::accept_waveform
+    pub fn into_stream<S>(self, audio: S) -> RecognitionStream<S>
+    where
+        S: Stream<Item = Vec<i16>>,
+    {
+        RecognitionStream::new(self, audio)
+    }
+}
+
+/// Feeds the chunks to `recognizer` and sends the events, until either of the channels is closed.
+fn recognize<R: SpeechRecognizer>(
+    mut recognizer: R,
+    mut chunks: mpsc::Receiver<Vec<i16>>,
+    events: mpsc::UnboundedSender<RecognitionEvent>,
+) {
+    let mut last_partial = LastPartial::default();
+
+    while let Some(chunk) = chunks.blocking_recv() {
+        let event = match recognizer.accept_waveform(&chunk) {
+            DecodingState::Running => match recognizer.try_partial_result() {
+                Ok(partial) if last_partial.changed(&partial) => {
+                    Some(RecognitionEvent::Partial(partial.into_owned()))
+                }
+                Ok(_) => None,
+                Err(err) => Some(RecognitionEvent::Error(err)),
+            },
+            DecodingState::Finalized => {
+                last_partial.clear();
+                Some(match recognizer.try_result() {
+                    Ok(result) => RecognitionEvent::Final(result.into_owned()),
+                    Err(err) => RecognitionEvent::Error(err),
+                })
+            }
+            DecodingState::Failed => Some(RecognitionEvent::Error(Error::DecodingFailed)),
+        };
+
+        if let Some(event) = event {
+            if events.send(event).is_err() {
+                return;
+            }
+        }
+    }
+
+    if events.is_closed() {
+        return;
+    }
+
+    let event = match recognizer.try_final_result() {
+        Ok(result) => RecognitionEvent::Final(result.into_owned()),
+        Err(err) => RecognitionEvent::Error(err),
+    };
+    // Freed before the stream ends, so that the recognizer's resources
+    // are released once the last event has been received
+    drop(recognizer);
+    let _ = events.send(event);
+}
@@ -0,0 +1,93 @@
+use crate::{models::path_to_cstring, Error};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    path::Path,
+    ptr::NonNull,
+};
+use vosk_sys::*;
+
+#[cfg(unix)]
+extern "C" {
+    fn free(ptr: *mut std::ffi::c_void);
+}
+
+/// Releases a string returned by `vosk_text_processor_itn`.
+///
+/// Vosk allocates it with `malloc` and has no function to release it. On Unix, the library
+/// shares the allocator of the process, so it is released with `free`. Elsewhere, like on
+/// Windows, the library may be built against a different C runtime with its own heap,
+/// where releasing it would be undefined behavior, so it is leaked instead.
+unsafe fn free_text(text: *mut c_char) {
+    #[cfg(unix)]
+    free(text.cast());
+    #[cfg(not(unix))]
+    let _ = text;
+}
+
+/// Inverse text normalization, which turns the spoken form returned by a [`Recognizer`]
+/// into its written form, like "twenty five dollars" into "$25".
+///
+/// Needs Vosk 0.3.50 or newer and the `vosk-0-3-50` feature.
+///
+/// [`Recognizer`]: crate::Recognizer
+pub struct TextProcessor(NonNull<VoskTextProcessor>);
+
+impl TextProcessor {
+    /// Loads a text processor from its tagger and verbalizer FSTs,
+    /// which are distributed with the models that support it.
+    pub fn new(tagger: impl AsRef<Path>, verbalizer: impl AsRef<Path>) -> Result<Self, Error> {
+        #[cfg(feature = "dynamic-load")]
+        crate::library::ensure_loaded()?;
+        let tagger = tagger.as_ref();
+        let verbalizer = verbalizer.as_ref();
+        for path in [tagger, verbalizer] {
+            if !path.is_file() {
+                return Err(Error::PathNotFound(path.to_path_buf()));
+            }
+        }
+
+        let tagger_c = path_to_cstring(tagger)?;
+        let verbalizer_c = path_to_cstring(verbalizer)?;
+        let processor_ptr =
+            unsafe { vosk_text_processor_new(tagger_c.as_ptr(), verbalizer_c.as_ptr()) };
+
+        Ok(Self(
+            NonNull::new(processor_ptr).ok_or(Error::NativeInitFailed)?,
+        ))
+    }
+
+    /// Returns the written form of `text`.
+    ///
+    /// NUL characters in `text` are ignored, as they cannot be passed to Vosk.
+    ///
+    /// Returns [`Error::NormalizationFailed`] if Vosk does not return any text.
+    ///
+    /// The memory of the text returned by Vosk is only released on Unix, as on other
+    /// targets it may belong to the heap of a different C runtime.
+    pub fn itn(&self, text: &str) -> Result<String, Error> {
+        let text_c = CString::new(text.replace('\0', "")).expect("NUL characters were removed");
+
+        unsafe {
+            let normalized_ptr = vosk_text_processor_itn(self.0.as_ptr(), text_c.as_ptr());
+            if normalized_ptr.is_null() {
+                return Err(Error::NormalizationFailed);
+            }
+
+            let normalized = CStr::from_ptr(normalized_ptr)
+                .to_string_lossy()
+                .into_owned();
+            free_text(normalized_ptr);
+
+            Ok(normalized)
+        }
+    }
+}
+
+impl Drop for TextProcessor {
+    fn drop(&mut self) {
+        unsafe { vosk_text_processor_free(self.0.as_ptr()) }
+    }
+}
+
+unsafe impl Send for TextProcessor {}
@@ -0,0 +1,165 @@
+use crate::{Word, WordInAlternative};
+use std::{
+    borrow::Cow,
+    time::{Duration, SystemTime},
+};
+
+/// Maps the times of the words in the results of a [`Recognizer`] to
+/// offsets in a stream of audio as [`Duration`]s, and to wall-clock times.
+///
+/// Vosk reports the times of the words in seconds since the first sample fed to the
+/// recognizer, and [resetting](crate::Recognizer::reset) it does not restart them, so the
+/// timeline returned by [`Recognizer::timeline`] starts at zero. A timeline with another
+/// [`origin`](Self::origin) maps the results of a recognizer that was created partway
+/// through the stream, like when a long recording is split across several of them.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::time::SystemTime;
+/// # use vosk::{Model, Recognizer};
+/// #
+/// let model = Model::new("/path/to/model").expect("Could not create a model");
+/// let mut recognizer =
+///     Recognizer::new(&model, model.sample_rate()).expect("Could not create a recognizer");
+/// recognizer.set_words(true);
+/// // The audio is captured live, starting now
+/// recognizer.set_wall_clock_anchor(SystemTime::now());
+///
+/// recognizer.accept_waveform(&[0; 16000]);
+///
+/// let timeline = recognizer.timeline();
+/// let result = recognizer.final_result().single().unwrap();
+/// for word in result.timed_words(&timeline) {
+///     println!("{}: {:.2}s ({:?})", word.word, word.start_secs(), word.start_time);
+/// }
+/// ```
+///
+/// [`Recognizer`]: crate::Recognizer
+/// [`Recognizer::timeline`]: crate::Recognizer::timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Timeline {
+    /// Offset in the stream of the first sample fed to the recognizer, which is
+    /// where the times it reports count from.
+    pub origin: Duration,
+
+    /// Wall-clock time of the start of the stream, for live audio
+    /// (see [`Recognizer::set_wall_clock_anchor`](crate::Recognizer::set_wall_clock_anchor)).
+    pub anchor: Option<SystemTime>,
+}
+
+impl Timeline {
+    /// Creates a timeline that starts at `origin`, without a wall-clock anchor.
+    #[must_use]
+    pub fn new(origin: Duration) -> Self {
+        Self {
+            origin,
+            anchor: None,
+        }
+    }
+
+    /// Sets the wall-clock time of the start of the stream.
+    #[must_use]
+    pub fn with_anchor(mut self, anchor: SystemTime) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    /// Returns the offset in the stream of a time reported by Vosk, in seconds.
+    ///
+    /// Negative or invalid times are treated as zero.
+    #[must_use]
+    pub fn offset(&self, seconds: f32) -> Duration {
+        self.origin + Duration::try_from_secs_f32(seconds).unwrap_or_default()
+    }
+
+    /// Returns the wall-clock time of an `offset` in the stream,
+    /// or [`None`] if the timeline has no anchor.
+    #[must_use]
+    pub fn wall_clock(&self, offset: Duration) -> Option<SystemTime> {
+        self.anchor?.checked_add(offset)
+    }
+
+    /// Returns `word` with its times in the timeline of the stream.
+    #[must_use]
+    pub fn word<'a>(&self, word: &'a Word<'_>) -> TimedWord<'a> {
+        self.timed(&word.word, Some(word.conf), word.start, word.end)
+    }
+
+    /// Returns a word of an [`Alternative`](crate::Alternative)
+    /// with its times in the timeline of the stream.
+    #[must_use]
+    pub fn word_in_alternative<'a>(&self, word: &'a WordInAlternative<'_>) -> TimedWord<'a> {
+        self.timed(&word.word, None, word.start, word.end)
+    }
+
+    fn timed<'a>(&self, word: &'a str, conf: Option<f32>, start: f32, end: f32) -> TimedWord<'a> {
+        let (start, end) = (self.offset(start), self.offset(end));
+
+        TimedWord {
+            word: Cow::Borrowed(word),
+            conf,
+            start,
+            end,
+            start_time: self.wall_clock(start),
+            end_time: self.wall_clock(end),
+        }
+    }
+}
+
+/// A recognized word with its times in the timeline of the
+/// whole stream, see [`Timeline`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedWord<'a> {
+    /// The transcription of the word.
+    pub word: Cow<'a, str>,
+
+    /// Confidence that the word was recognized correctly, between 0 and 1.
+    /// [`None`] for the words of alternatives, which do not have one.
+    pub conf: Option<f32>,
+
+    /// Offset in the stream where the word starts.
+    pub start: Duration,
+
+    /// Offset in the stream where the word ends.
+    pub end: Duration,
+
+    /// Wall-clock time when the word started, if the timeline has an anchor.
+    pub start_time: Option<SystemTime>,
+
+    /// Wall-clock time when the word ended, if the timeline has an anchor.
+    pub end_time: Option<SystemTime>,
+}
+
+impl TimedWord<'_> {
+    /// Returns [`start`](Self::start) in seconds.
+    #[must_use]
+    pub fn start_secs(&self) -> f64 {
+        self.start.as_secs_f64()
+    }
+
+    /// Returns [`end`](Self::end) in seconds.
+    #[must_use]
+    pub fn end_secs(&self) -> f64 {
+        self.end.as_secs_f64()
+    }
+
+    /// Returns how long the word lasts.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Converts `self` into a [`TimedWord`] that does not borrow from the result.
+    #[must_use]
+    pub fn into_owned(self) -> TimedWord<'static> {
+        TimedWord {
+            word: Cow::Owned(self.word.into_owned()),
+            conf: self.conf,
+            start: self.start,
+            end: self.end,
+            start_time: self.start_time,
+            end_time: self.end_time,
+        }
+    }
+}
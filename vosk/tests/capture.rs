@@ -0,0 +1,81 @@
+//! Tests of `capture_stderr`, run with `cargo test --features log`.
+//!
+//! They redirect the process' stderr and install a global logger,
+//! so they have their own binary instead of running with other tests.
+
+#![cfg(all(unix, not(feature = "tracing")))]
+
+use std::{
+    io::{self, Write},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use vosk::capture_stderr;
+
+static EMITTED: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes every record to stderr, like `env_logger`, padded so that
+/// the pipe fills up if nothing drains it while they are written.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        EMITTED.fetch_add(1, Ordering::Relaxed);
+        let message = record.args().to_string();
+        eprintln!("[{}] {message:4096}", record.level());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Turns the return value of a libc function into an [`io::Result`].
+fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
+
+#[test]
+fn records_written_to_stderr_and_child_processes_do_not_block() {
+    const MESSAGES: usize = 1000;
+
+    log::set_logger(&StderrLogger).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    // Everything that is passed through is discarded
+    let null = std::fs::File::create("/dev/null").unwrap();
+    let original = unsafe { OwnedFd::from_raw_fd(check(libc::dup(2)).unwrap()) };
+    check(unsafe { libc::dup2(null.as_raw_fd(), 2) }).unwrap();
+
+    let capture = capture_stderr().unwrap();
+    assert!(capture_stderr().is_err());
+
+    // The records written to the pipe are much more than its capacity
+    let mut stderr = io::stderr();
+    for i in 0..MESSAGES {
+        writeln!(stderr, "LOG (VoskAPI:Test():test.cc:{i}) Message {i}").unwrap();
+    }
+
+    // Keeps the pipe open for longer than the capture waits
+    let mut child = std::process::Command::new("sleep")
+        .arg("5")
+        .spawn()
+        .unwrap();
+
+    let start = Instant::now();
+    drop(capture);
+    assert!(start.elapsed() < Duration::from_secs(3));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    check(unsafe { libc::dup2(original.as_raw_fd(), 2) }).unwrap();
+    assert_eq!(EMITTED.load(Ordering::Relaxed), MESSAGES);
+    assert!(capture_stderr().is_ok());
+}
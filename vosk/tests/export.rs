@@ -0,0 +1,66 @@
+//! Tests of the captions against the reference files in `tests/captions`.
+
+use std::time::Duration;
+use vosk::{
+    export::{CaptionOptions, Captions},
+    CompleteResultSingle, Timeline, Word,
+};
+
+const RESULTS: &str = include_str!("captions/results.json");
+
+fn options() -> CaptionOptions {
+    CaptionOptions {
+        max_line_length: 16,
+        max_lines: 2,
+        max_cue_duration: Duration::from_secs(4),
+        split_on_pause: Some(Duration::from_millis(700)),
+        low_confidence: Some(0.5),
+    }
+}
+
+fn results() -> Vec<CompleteResultSingle<'static>> {
+    serde_json::from_str(RESULTS).expect("Could not parse the results")
+}
+
+#[test]
+fn srt_matches_the_reference() {
+    let results = results();
+    let captions = Captions::from_results(&results, &options());
+
+    assert_eq!(captions.to_srt(), include_str!("captions/reference.srt"));
+}
+
+#[test]
+fn vtt_matches_the_reference() {
+    let results = results();
+    let captions = Captions::from_results(&results, &options());
+
+    assert_eq!(captions.to_vtt(), include_str!("captions/reference.vtt"));
+}
+
+#[test]
+fn long_words_and_rebased_times() {
+    let word = |word: &'static str, start, end| Word {
+        conf: 1.0,
+        start,
+        end,
+        word: word.into(),
+    };
+    let words = [
+        word("a", 0.0, 0.5),
+        word("supercalifragilistic", 0.5, 1.5),
+        word("b", 1.5, 2.0),
+    ];
+    let timeline = Timeline::new(Duration::from_secs(10));
+    let options = CaptionOptions {
+        max_lines: 3,
+        ..options()
+    };
+
+    let captions = Captions::new(words.iter().map(|word| timeline.word(word)), &options);
+    let cues = captions.cues();
+    assert_eq!(cues.len(), 1);
+    assert_eq!(cues[0].start, Duration::from_secs(10));
+    assert_eq!(cues[0].end, Duration::from_secs(12));
+    assert_eq!(cues[0].text_lines(), ["a", "supercalifragilistic", "b"]);
+}
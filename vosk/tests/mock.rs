@@ -0,0 +1,817 @@
+//! Tests against the mocked library, run with `cargo test --features mock`.
+
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use vosk::{
+    mock::{partial_json, text_json, write_model_dir, Mock, MockRecognizer},
+    BatchModel, BatchRecognizer, ChannelLayout, CompleteResult, DecodingState, EndpointerDelays,
+    EndpointingRecognizer, Error, Grammar, GraphKind, Model, Recognizer, RecordingRecognizer,
+    ReplayRecognizer, SpeechRecognizer, Timeline,
+};
+
+/// Creates a new model directory for a test.
+fn model_dir(graph: GraphKind) -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!(
+        "vosk-mock-{}-{}",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    write_model_dir(&dir, graph).expect("Could not create the model directory");
+
+    dir
+}
+
+fn model() -> Model {
+    Model::new(model_dir(GraphKind::Lookahead)).expect("Could not create the model")
+}
+
+#[test]
+fn model_load_failure_is_reported() {
+    let _guard = Mock::new().fail_model_load().install();
+
+    let result = Model::new(model_dir(GraphKind::Lookahead));
+    assert!(matches!(result, Err(Error::NativeInitFailed)));
+}
+
+#[test]
+fn missing_model_files_are_reported_before_loading() {
+    let dir = model_dir(GraphKind::Lookahead);
+    std::fs::remove_file(dir.join("am/final.mdl")).unwrap();
+
+    match Model::new(&dir) {
+        Err(Error::MissingModelFile { file }) => assert_eq!(file, PathBuf::from("am/final.mdl")),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+}
+
+#[test]
+fn invalid_frame_shift_is_reported() {
+    for value in ["-10", "NaN", "1e39", "1e30", "0"] {
+        let dir = model_dir(GraphKind::Lookahead);
+        std::fs::write(
+            dir.join("conf/mfcc.conf"),
+            format!("--sample-frequency=16000\n--frame-shift={value}\n"),
+        )
+        .unwrap();
+
+        match Model::new(&dir) {
+            Err(Error::InvalidModelConfig { file, .. }) => {
+                assert_eq!(file, PathBuf::from("conf/mfcc.conf"), "{value}")
+            }
+            other => panic!("unexpected result for {value}: {:?}", other.err()),
+        }
+    }
+}
+
+#[test]
+fn invalid_sample_frequency_is_reported() {
+    for value in ["0", "-16000", "NaN", "inf"] {
+        let dir = model_dir(GraphKind::Lookahead);
+        std::fs::write(
+            dir.join("conf/mfcc.conf"),
+            format!("--sample-frequency={value}\n"),
+        )
+        .unwrap();
+
+        match Model::new(&dir) {
+            Err(Error::InvalidModelConfig { file, .. }) => {
+                assert_eq!(file, PathBuf::from("conf/mfcc.conf"), "{value}")
+            }
+            other => panic!("unexpected result for {value}: {:?}", other.err()),
+        }
+    }
+}
+
+#[test]
+fn recognizer_creation_failure_is_reported() {
+    let _guard = Mock::new().fail_recognizer_creation().install();
+    let model = model();
+
+    let result = Recognizer::new(&model, 16000.0);
+    assert!(matches!(result, Err(Error::NativeInitFailed)));
+}
+
+#[test]
+fn decoding_follows_the_script() {
+    let script = MockRecognizer::new()
+        .partial(partial_json("hello"))
+        .finalized(text_json("hello world"))
+        .failed();
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 8000.0).unwrap();
+
+    assert_eq!(
+        recognizer.accept_waveform(&[0; 100]),
+        DecodingState::Running
+    );
+    assert_eq!(recognizer.partial_result().partial, "hello");
+
+    assert_eq!(
+        recognizer.accept_waveform(&[0; 100]),
+        DecodingState::Finalized
+    );
+    assert_eq!(recognizer.result().single().unwrap().text, "hello world");
+    assert_eq!(recognizer.partial_result().partial, "");
+
+    assert_eq!(recognizer.accept_waveform(&[0; 100]), DecodingState::Failed);
+    assert_eq!(
+        recognizer.accept_waveform(&[0; 100]),
+        DecodingState::Running
+    );
+
+    assert_eq!(script.sample_rate(), 8000.0);
+    assert_eq!(script.accepted_samples(), 400);
+    assert_eq!(script.remaining_steps(), 0);
+}
+
+#[test]
+fn samples_are_converted_from_every_format() {
+    let script = MockRecognizer::new();
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    recognizer.accept_waveform(&[0; 10]);
+    recognizer.accept_waveform_f32(&[0.0; 10]);
+    recognizer.accept_waveform_bytes(&[0; 21]);
+    recognizer.accept_interleaved(&[0i16; 20], ChannelLayout::Downmix { channels: 2 });
+
+    assert_eq!(script.accepted_samples(), 10 + 10 + 10 + 10);
+}
+
+#[test]
+fn bytes_split_between_chunks_are_joined_into_samples() {
+    let script = MockRecognizer::new();
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    let samples: Vec<i16> = (0..100).map(|i| i * 300 - 15000).collect();
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    for chunk in bytes.chunks(7) {
+        recognizer.accept_waveform_bytes(chunk);
+    }
+
+    let expected: Vec<f32> = samples.iter().map(|&sample| f32::from(sample)).collect();
+    assert_eq!(script.samples(), expected);
+    assert_eq!(recognizer.position(), Duration::from_micros(6250));
+
+    // The byte left over from an odd chunk is only fed once the sample is complete
+    recognizer.accept_waveform_bytes(&[0x34]);
+    assert_eq!(script.accepted_samples(), 100);
+    recognizer.accept_waveform_bytes(&[0x12, 0xff]);
+    assert_eq!(script.samples()[100..], [f32::from(0x1234i16)]);
+    assert_eq!(script.accepted_samples(), 101);
+}
+
+#[test]
+fn multichannel_recognizers_decode_each_channel() {
+    use vosk::MultichannelRecognizer;
+
+    let (left, right) = (
+        MockRecognizer::new().finalized(text_json("left")),
+        MockRecognizer::new().partial(partial_json("right")),
+    );
+    let _guard = Mock::new()
+        .recognizer(left.clone())
+        .recognizer(right.clone())
+        .install();
+    let model = model();
+    let recognizers = vec![
+        Recognizer::new(&model, 16000.0).unwrap(),
+        Recognizer::new(&model, 16000.0).unwrap(),
+    ];
+    let mut recognizer = MultichannelRecognizer::new(recognizers).unwrap();
+
+    assert_eq!(
+        recognizer.accept_waveform(&[0; 21]),
+        [DecodingState::Finalized, DecodingState::Running]
+    );
+    assert_eq!(
+        (left.accepted_samples(), right.accepted_samples()),
+        (10, 10)
+    );
+
+    let results = recognizer.finalized_results();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].channel, 0);
+
+    assert!(matches!(
+        MultichannelRecognizer::new(Vec::new()),
+        Err(Error::InvalidChannelCount(0))
+    ));
+    let too_many = (0..=u16::MAX)
+        .map(|_| Recognizer::new(&model, 16000.0).unwrap())
+        .collect();
+    assert!(matches!(
+        MultichannelRecognizer::new(too_many),
+        Err(Error::InvalidChannelCount(65536))
+    ));
+}
+
+#[test]
+fn frames_split_between_chunks_keep_their_channels() {
+    use vosk::MultichannelRecognizer;
+
+    let (left, right, mixed) = (
+        MockRecognizer::new(),
+        MockRecognizer::new(),
+        MockRecognizer::new(),
+    );
+    let _guard = Mock::new()
+        .recognizer(left.clone())
+        .recognizer(right.clone())
+        .recognizer(mixed.clone())
+        .install();
+    let model = model();
+    let mut multichannel = MultichannelRecognizer::new(vec![
+        Recognizer::new(&model, 16000.0).unwrap(),
+        Recognizer::new(&model, 16000.0).unwrap(),
+    ])
+    .unwrap();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    // Stereo audio with positive samples on the left and negative ones on the right
+    let samples: Vec<i16> = (1..=50).flat_map(|i| [i * 10, -i * 20]).collect();
+    for chunk in samples.chunks(3) {
+        multichannel.accept_waveform(chunk);
+        recognizer.accept_interleaved(chunk, ChannelLayout::Downmix { channels: 2 });
+    }
+
+    let expected = |f: fn(f32) -> f32| (1..=50).map(|i| f(i as f32)).collect::<Vec<_>>();
+    assert_eq!(left.samples(), expected(|i| i * 10.0));
+    assert_eq!(right.samples(), expected(|i| -i * 20.0));
+    assert_eq!(mixed.samples(), expected(|i| -i * 5.0));
+}
+
+#[test]
+fn words_and_alternatives_are_parsed() {
+    let script = MockRecognizer::new()
+        .finalized(
+            r#"{
+                "result": [
+                    {"conf": 1.0, "start": 0.5, "end": 0.9, "word": "hello"},
+                    {"conf": 0.75, "start": 1.0, "end": 1.4, "word": "world"}
+                ],
+                "text": "hello world"
+            }"#,
+        )
+        .finalized(
+            r#"{
+                "alternatives": [
+                    {"confidence": 210.5, "result": [], "text": "one"},
+                    {"confidence": 200.0, "result": [], "text": "won"}
+                ]
+            }"#,
+        );
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+    recognizer.set_words(true);
+
+    recognizer.accept_waveform(&[0; 100]);
+    let result = recognizer.result().single().unwrap();
+    assert_eq!(result.result.len(), 2);
+    assert_eq!(result.result[1].word, "world");
+    assert_eq!(result.result[1].conf, 0.75);
+    assert_eq!(result.result[1].start, 1.0);
+
+    recognizer.set_max_alternatives(2);
+    recognizer.accept_waveform(&[0; 100]);
+    let texts: Vec<_> = match recognizer.result() {
+        CompleteResult::Multiple(result) => result
+            .alternatives
+            .into_iter()
+            .map(|alternative| alternative.text.into_owned())
+            .collect(),
+        CompleteResult::Single(_) => panic!("expected alternatives"),
+    };
+    assert_eq!(texts, ["one", "won"]);
+
+    assert!(script.words());
+    assert_eq!(script.max_alternatives(), 2);
+}
+
+#[test]
+fn escaped_text_is_unescaped() {
+    let script = MockRecognizer::new().finalized(text_json("say \"hi\"\\"));
+    let _guard = Mock::new().recognizer(script).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    recognizer.accept_waveform(&[0; 100]);
+    assert_eq!(recognizer.result().single().unwrap().text, "say \"hi\"\\");
+}
+
+#[test]
+fn invalid_results_are_errors() {
+    let script = MockRecognizer::new()
+        .partial("not json")
+        .finalized(r#"{"text": 5}"#);
+    let _guard = Mock::new().recognizer(script).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    recognizer.accept_waveform(&[0; 100]);
+    match recognizer.try_partial_result() {
+        Err(Error::InvalidResultJson { json, .. }) => assert_eq!(json, "not json"),
+        other => panic!("unexpected result {other:?}"),
+    }
+
+    recognizer.accept_waveform(&[0; 100]);
+    assert!(matches!(
+        recognizer.try_result(),
+        Err(Error::InvalidResultJson { .. })
+    ));
+}
+
+#[test]
+fn invalid_results_of_one_channel_are_errors() {
+    use vosk::MultichannelRecognizer;
+
+    let (left, right) = (
+        MockRecognizer::new().finalized(text_json("left")),
+        MockRecognizer::new().finalized("not json"),
+    );
+    let _guard = Mock::new().recognizer(left).recognizer(right).install();
+    let model = model();
+    let mut recognizer = MultichannelRecognizer::new(vec![
+        Recognizer::new(&model, 16000.0).unwrap(),
+        Recognizer::new(&model, 16000.0).unwrap(),
+    ])
+    .unwrap();
+
+    recognizer.accept_waveform(&[0; 20]);
+    match recognizer.try_finalized_results() {
+        Err(Error::InvalidResultJson { json, .. }) => assert_eq!(json, "not json"),
+        other => panic!("unexpected result {other:?}"),
+    }
+    assert!(recognizer.try_partial_results().is_ok());
+}
+
+#[test]
+fn final_result_and_reset() {
+    let script = MockRecognizer::new()
+        .partial(partial_json("one"))
+        .final_result(text_json("one two"));
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    recognizer.accept_waveform(&[0; 100]);
+    assert_eq!(recognizer.final_result().single().unwrap().text, "one two");
+    assert_eq!(recognizer.final_result().single().unwrap().text, "");
+
+    recognizer.reset();
+    assert_eq!(script.resets(), 1);
+}
+
+#[test]
+fn word_times_are_mapped_to_the_stream_timeline() {
+    use std::time::UNIX_EPOCH;
+
+    // Vosk keeps counting the times across resets
+    let script = MockRecognizer::new()
+        .partial(partial_json("one"))
+        .finalized(
+            r#"{
+                "result": [{"conf": 1.0, "start": 1.75, "end": 2.0, "word": "two"}],
+                "text": "two"
+            }"#,
+        );
+    let _guard = Mock::new().recognizer(script).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 1000.0).unwrap();
+    let anchor = UNIX_EPOCH + Duration::from_secs(60);
+    recognizer.set_wall_clock_anchor(anchor);
+
+    recognizer.accept_waveform(&[0; 1500]);
+    recognizer.reset();
+    recognizer.accept_samples(&[0.0f32; 500]);
+    assert_eq!(recognizer.position(), Duration::from_secs(2));
+
+    let timeline = recognizer.timeline();
+    assert_eq!(timeline.origin, Duration::ZERO);
+    let result = recognizer.result().single().unwrap();
+    let words = result.timed_words(&timeline);
+    assert_eq!(words.len(), 1);
+    assert_eq!(words[0].word, "two");
+    assert_eq!(words[0].start, Duration::from_millis(1750));
+    assert_eq!(words[0].end_secs(), 2.0);
+    assert_eq!(
+        words[0].start_time,
+        Some(anchor + Duration::from_millis(1750))
+    );
+
+    // A recognizer that started later in the stream
+    let words = result.timed_words(&Timeline::new(Duration::from_secs(10)));
+    assert_eq!(words[0].start, Duration::from_millis(11750));
+    assert_eq!(words[0].start_time, None);
+}
+
+#[test]
+fn recognizer_is_freed_when_dropped() {
+    let script = MockRecognizer::new();
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+
+    let recognizer = Recognizer::new(&model, 16000.0).unwrap();
+    assert!(!script.is_freed());
+    drop(recognizer);
+    assert!(script.is_freed());
+}
+
+#[test]
+fn grammar_is_passed_as_json() {
+    let script = MockRecognizer::new();
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let grammar = Grammar::from_phrases(["turn \"on\"", "turn off"]).with_unk();
+
+    let _recognizer = Recognizer::new_with_grammar(&model, 16000.0, &grammar).unwrap();
+    assert_eq!(
+        script.grammar().as_deref(),
+        Some(r#"["turn \"on\"","turn off","[unk]"]"#)
+    );
+}
+
+#[test]
+fn grammar_is_rejected_for_static_graphs() {
+    let model = Model::new(model_dir(GraphKind::Static)).unwrap();
+
+    let result = Recognizer::new_with_grammar(&model, 16000.0, &Grammar::from_phrases(["yes"]));
+    assert!(matches!(result, Err(Error::GrammarNotSupported)));
+}
+
+#[test]
+fn out_of_vocabulary_words_are_found() {
+    let _guard = Mock::new().vocabulary(["yes", "no"]).install();
+    let model = model();
+
+    assert_eq!(model.find_word("no"), Some(2));
+    assert_eq!(model.find_word("maybe"), None);
+
+    let grammar = Grammar::from_phrases(["yes", "no maybe"]);
+    match grammar.validate(&model) {
+        Err(Error::OutOfVocabulary(phrases)) => {
+            assert_eq!(phrases.len(), 1);
+            assert_eq!(phrases[0].phrase, "no maybe");
+            assert_eq!(phrases[0].words, ["maybe"]);
+        }
+        other => panic!("unexpected result {other:?}"),
+    }
+}
+
+#[test]
+fn endpointer_ends_utterances_after_silence() {
+    // Partial result where "hello" ends at `end` seconds
+    let hello = |end: f32| {
+        format!(
+            r#"{{
+                "partial_result": [{{"conf": 1.0, "start": 0.0, "end": {end}, "word": "hello"}}],
+                "partial": "hello"
+            }}"#
+        )
+    };
+    let script = MockRecognizer::new()
+        .partial(hello(0.125))
+        .partial(hello(0.25))
+        .partial(hello(0.375))
+        .partial(hello(0.375))
+        .partial(hello(0.375))
+        .final_result(text_json("hello"));
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let recognizer = Recognizer::new(&model, 1000.0).unwrap();
+
+    let delays = EndpointerDelays {
+        start_max: Duration::from_millis(375),
+        end: Duration::from_millis(250),
+        max: Duration::from_secs(20),
+    };
+    let mut recognizer = EndpointingRecognizer::new(recognizer, delays);
+    assert!(script.partial_words());
+
+    // Each chunk is 125ms long. The text does not change while the word is still being
+    // spoken, so the utterance only ends 250ms after the end of the word
+    let chunk = [0i16; 125];
+    for _ in 0..4 {
+        assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Running);
+    }
+    assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Finalized);
+    assert_eq!(recognizer.result().single().unwrap().text, "hello");
+
+    // Without any words, the utterance ends after `start_max`
+    assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Running);
+    assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Running);
+    assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Finalized);
+    assert_eq!(recognizer.result().single().unwrap().text, "");
+}
+
+#[test]
+fn endpointer_results_are_only_kept_until_the_next_chunk() {
+    let script = MockRecognizer::new()
+        .partial(partial_json(""))
+        .partial(partial_json(""))
+        .finalized(text_json("from vosk"))
+        .final_result(text_json("from the endpointer"));
+    let _guard = Mock::new().recognizer(script).install();
+    let model = model();
+    let recognizer = Recognizer::new(&model, 1000.0).unwrap();
+
+    let delays = EndpointerDelays {
+        start_max: Duration::from_millis(250),
+        end: Duration::from_millis(250),
+        max: Duration::from_secs(20),
+    };
+    let mut recognizer = EndpointingRecognizer::new(recognizer, delays);
+
+    // The result of the utterance ended by the endpointer is never retrieved
+    let chunk = [0i16; 125];
+    assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Running);
+    assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Finalized);
+
+    assert_eq!(recognizer.accept_waveform(&chunk), DecodingState::Finalized);
+    assert_eq!(recognizer.result().single().unwrap().text, "from vosk");
+}
+
+#[test]
+fn batch_results_are_drained() {
+    let script = MockRecognizer::new()
+        .finalized(text_json("first"))
+        .final_result(text_json("last"));
+    let _guard = Mock::new().recognizer(script).install();
+    let model = BatchModel::new().unwrap();
+    let mut recognizer = BatchRecognizer::new(&model, 16000.0).unwrap();
+
+    recognizer.accept_waveform(&[0; 100]);
+    recognizer.finish_stream();
+
+    let texts: Vec<_> = recognizer
+        .results()
+        .map(|result| result.unwrap().single().unwrap().text.into_owned())
+        .collect();
+    assert_eq!(texts, ["first", "last"]);
+    assert_eq!(recognizer.results().count(), 0);
+}
+
+#[test]
+fn recordings_play_back_the_same_results() {
+    let script = MockRecognizer::new()
+        .partial(partial_json("hello"))
+        .finalized(text_json("hello world"))
+        .failed()
+        .final_result(text_json("bye"));
+    let _guard = Mock::new().recognizer(script).install();
+    let model = model();
+    let recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    fn run(recognizer: &mut dyn SpeechRecognizer) -> (Vec<DecodingState>, Vec<String>) {
+        let mut states = Vec::new();
+        let mut texts = Vec::new();
+        for _ in 0..3 {
+            let state = recognizer.accept_waveform(&[0; 100]);
+            match state {
+                DecodingState::Running => texts.push(recognizer.partial_result().partial.into()),
+                DecodingState::Finalized => {
+                    texts.push(recognizer.result().single().unwrap().text.into())
+                }
+                DecodingState::Failed => {}
+            }
+            states.push(state);
+        }
+        texts.push(recognizer.final_result().single().unwrap().text.into());
+
+        (states, texts)
+    }
+
+    let mut recording = RecordingRecognizer::new(recognizer, Vec::new());
+    let recorded = run(&mut recording);
+    assert_eq!(recording.position(), Duration::from_micros(18750));
+    let (_, lines) = recording.finish().unwrap();
+
+    let mut replay = ReplayRecognizer::from_reader(lines.as_slice(), 16000.0).unwrap();
+    assert_eq!(run(&mut replay), recorded);
+    assert!(replay.is_finished());
+    assert_eq!(replay.position(), Duration::from_micros(18750));
+    assert_eq!(
+        recorded.0,
+        [
+            DecodingState::Running,
+            DecodingState::Finalized,
+            DecodingState::Failed
+        ]
+    );
+    assert_eq!(recorded.1, ["hello", "hello world", "bye"]);
+}
+
+#[test]
+fn sessions_report_changed_partial_results_and_utterance_offsets() {
+    use std::sync::{Arc, Mutex};
+    use vosk::StreamingSession;
+
+    let script = MockRecognizer::new()
+        .partial(partial_json("one"))
+        .partial(partial_json("one"))
+        .finalized(text_json("one two"))
+        .finalized(text_json(""))
+        .partial(partial_json("three"))
+        .final_result(text_json("three"));
+    let _guard = Mock::new().recognizer(script).install();
+    let model = model();
+    let recognizer = Recognizer::new(&model, 1000.0).unwrap();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let (partials, finals, silences) = (events.clone(), events.clone(), events.clone());
+    let mut session = StreamingSession::new(recognizer)
+        .on_partial(move |partial| {
+            partials
+                .lock()
+                .unwrap()
+                .push(format!("partial {}", partial.partial))
+        })
+        .on_final(move |utterance| {
+            let text = utterance.result.single().unwrap().text;
+            let span = utterance.start.as_millis()..utterance.end.as_millis();
+            finals
+                .lock()
+                .unwrap()
+                .push(format!("final {text} {span:?}"));
+        })
+        .on_silence(move |span| {
+            let span = span.start.as_millis()..span.end.as_millis();
+            silences.lock().unwrap().push(format!("silence {span:?}"));
+        });
+
+    // Each chunk is 100ms long
+    for _ in 0..5 {
+        session.accept_waveform(&[0; 100]).unwrap();
+    }
+    session.finish().unwrap();
+    session.finish().unwrap();
+
+    assert_eq!(session.elapsed(), Duration::from_millis(500));
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "partial one",
+            "final one two 0..300",
+            "silence 300..400",
+            "partial three",
+            "final three 400..500"
+        ]
+    );
+}
+
+#[test]
+fn session_offsets_follow_the_recognizer_position() {
+    use std::sync::{Arc, Mutex};
+    use vosk::StreamingSession;
+
+    let script = MockRecognizer::new()
+        .partial(partial_json(""))
+        .partial(partial_json(""))
+        .finalized(text_json("hello"));
+    let _guard = Mock::new().recognizer(script).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 1000.0).unwrap();
+    recognizer.accept_waveform(&[0; 250]);
+
+    let spans = Arc::new(Mutex::new(Vec::new()));
+    let finals = spans.clone();
+    let mut session = StreamingSession::new(recognizer).on_final(move |utterance| {
+        finals.lock().unwrap().push(utterance.start..utterance.end);
+    });
+    // Audio fed around the session is counted too, like in the times of the words
+    session.recognizer_mut().accept_waveform(&[0; 100]);
+    session.accept_waveform(&[0; 100]).unwrap();
+
+    assert_eq!(session.elapsed(), session.recognizer().position());
+    assert_eq!(
+        *spans.lock().unwrap(),
+        [Duration::from_millis(250)..Duration::from_millis(450)]
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn streams_yield_changed_partial_results_and_the_final_result() {
+    use futures::{executor::block_on, stream, StreamExt};
+    use vosk::RecognitionEvent;
+
+    let script = MockRecognizer::new()
+        .partial(partial_json("one"))
+        .partial(partial_json("one"))
+        .finalized(text_json("one two"))
+        .failed()
+        .final_result(text_json("three"));
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    let audio = stream::iter(vec![vec![0i16; 100]; 4]);
+    let events = block_on(recognizer.into_stream(audio).collect::<Vec<_>>());
+
+    let events: Vec<_> = events
+        .into_iter()
+        .map(|event| match event {
+            RecognitionEvent::Partial(partial) => format!("partial {}", partial.partial),
+            RecognitionEvent::Final(result) => format!("final {}", result.single().unwrap().text),
+            RecognitionEvent::Error(err) => format!("error {err}"),
+        })
+        .collect();
+    assert_eq!(
+        events,
+        [
+            "partial one",
+            "final one two",
+            "error vosk could not decode the audio",
+            "final three"
+        ]
+    );
+    assert_eq!(script.accepted_samples(), 400);
+    assert!(script.is_freed());
+}
+
+#[cfg(feature = "vosk-0-3-45")]
+#[test]
+fn grammar_can_be_changed_and_cleared() {
+    let script = MockRecognizer::new();
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    recognizer
+        .set_grammar(&Grammar::from_phrases(["yes", "no"]))
+        .unwrap();
+    assert_eq!(script.grammar().as_deref(), Some(r#"["yes","no"]"#));
+
+    recognizer.clear_grammar();
+    assert_eq!(script.grammar().as_deref(), Some("[]"));
+}
+
+#[cfg(feature = "vosk-0-3-50")]
+#[test]
+fn endpointer_configuration_is_passed_to_vosk() {
+    use vosk::EndpointerMode;
+
+    let script = MockRecognizer::new();
+    let _guard = Mock::new().recognizer(script.clone()).install();
+    let model = model();
+    let mut recognizer = Recognizer::new(&model, 16000.0).unwrap();
+
+    recognizer.set_endpointer_mode(EndpointerMode::Long);
+    assert_eq!(
+        script.endpointer_mode(),
+        Some(vosk_sys::VoskEndpointerMode_VOSK_EP_ANSWER_LONG)
+    );
+
+    recognizer.set_endpointer_delays(EndpointerDelays::from_mode(EndpointerMode::Short));
+    assert_eq!(script.endpointer_delays(), Some((5.0, 0.25, 20.0)));
+}
+
+#[cfg(feature = "vosk-0-3-50")]
+#[test]
+fn text_processor_applies_itn() {
+    let dir = model_dir(GraphKind::Lookahead);
+    let (tagger, verbalizer) = (dir.join("itn/tagger.fst"), dir.join("itn/verbalizer.fst"));
+    std::fs::create_dir_all(dir.join("itn")).unwrap();
+    std::fs::write(&tagger, "").unwrap();
+    std::fs::write(&verbalizer, "").unwrap();
+
+    let mock = Mock::new().itn("twenty five", "25");
+    let _guard = mock.install();
+    let processor = vosk::TextProcessor::new(&tagger, &verbalizer).unwrap();
+
+    assert_eq!(processor.itn("twenty five dollars").unwrap(), "25 dollars");
+    assert_eq!(processor.itn("twenty\0 five").unwrap(), "25");
+    assert_eq!(
+        mock.text_processors(),
+        [(
+            tagger.display().to_string(),
+            verbalizer.display().to_string()
+        )]
+    );
+}
+
+#[cfg(feature = "vosk-0-3-50")]
+#[test]
+fn text_processor_failure_is_an_error() {
+    let dir = model_dir(GraphKind::Lookahead);
+    let path = dir.join("itn.fst");
+    std::fs::write(&path, "").unwrap();
+
+    let _guard = Mock::new().fail_itn().install();
+    let processor = vosk::TextProcessor::new(&path, &path).unwrap();
+
+    assert!(matches!(
+        processor.itn("twenty five"),
+        Err(Error::NormalizationFailed)
+    ));
+}